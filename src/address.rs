@@ -0,0 +1,113 @@
+/// Trims an address and checks that it has a basic `local@domain` structure.
+///
+/// An address can be given in a `Name <local@domain>` form,
+/// in which case only the part in angle brackets is checked.
+///
+/// The check is intentionally lenient so it doesn't reject
+/// valid but unusual addresses like quoted local parts or IDN domains.
+///
+/// With the `idna` feature the domain is converted to its ASCII (punycode) form.
+pub(crate) fn normalize(address: &str) -> Option<String> {
+    let address = address.trim();
+    let (prefix, mailbox, suffix) = match (address.rfind('<'), address.ends_with('>')) {
+        (Some(start), true) => (
+            &address[..=start],
+            &address[start + 1..address.len() - 1],
+            ">",
+        ),
+        _ => ("", address, ""),
+    };
+
+    let at = mailbox.rfind('@')?;
+    let (local, domain) = (&mailbox[..at], &mailbox[at + 1..]);
+    if local.is_empty() || !is_domain(domain) {
+        return None;
+    }
+
+    let domain = to_ascii(domain)?;
+
+    Some(format!("{}{}@{}{}", prefix, local, domain, suffix))
+}
+
+fn is_domain(domain: &str) -> bool {
+    !domain.is_empty()
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains("..")
+        && !domain.chars().any(|c| c.is_whitespace() || c == '@')
+}
+
+#[cfg(feature = "idna")]
+fn to_ascii(domain: &str) -> Option<String> {
+    idna::domain_to_ascii(domain).ok()
+}
+
+#[cfg(not(feature = "idna"))]
+fn to_ascii(domain: &str) -> Option<String> {
+    Some(domain.to_owned())
+}
+
+/// Returns the `local@domain` part of an address
+/// which may be given in a `Name <local@domain>` form.
+pub(crate) fn mailbox(address: &str) -> &str {
+    let address = address.trim();
+    match (address.rfind('<'), address.ends_with('>')) {
+        (Some(start), true) => &address[start + 1..address.len() - 1],
+        _ => address,
+    }
+}
+
+/// Formats an address with a display name as `"Name" <local@domain>`
+///
+/// Quotes and backslashes in the name are escaped,
+/// a non ASCII name is written as an RFC 2047 encoded word.
+/// An empty name gives the bare address.
+pub(crate) fn with_name(name: &str, address: &str) -> String {
+    let (name, address) = (name.trim(), mailbox(address));
+    if name.is_empty() {
+        address.to_owned()
+    } else if name.is_ascii() {
+        let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\" <{}>", name, address)
+    } else {
+        format!("{} <{}>", crate::mime::encode_word(name), address)
+    }
+}
+
+/// Returns a key which is equal for addresses of the same mailbox
+///
+/// Display names are ignored and the domain is compared case-insensitively,
+/// a local part may be case sensitive so it's kept as is.
+pub(crate) fn mailbox_key(address: &str) -> String {
+    let mailbox = mailbox(address);
+    match mailbox.rfind('@') {
+        Some(at) => format!("{}@{}", &mailbox[..at], mailbox[at + 1..].to_lowercase()),
+        None => mailbox.to_owned(),
+    }
+}
+
+/// Splits a header value like `A <a@x.io>, "B, C" <b@x.io>`
+/// into separate addresses.
+pub(crate) fn split_list(list: &str) -> Vec<&str> {
+    let mut addresses = Vec::new();
+    let (mut quoted, mut bracketed, mut start) = (false, false, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => bracketed = true,
+            '>' if !quoted => bracketed = false,
+            ',' if !quoted && !bracketed => {
+                addresses.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    addresses.push(&list[start..]);
+
+    addresses
+        .into_iter()
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .collect()
+}
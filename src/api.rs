@@ -0,0 +1,108 @@
+use crate::{Client, DetailsInterest, Message, MessageHash, PostalError, RawMessage, SendResult};
+use async_trait::async_trait;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+
+/// PostalApi abstracts the methods of [Client]
+///
+/// Code which depends on the trait rather than on [Client]
+/// can be tested with a mock implementation.
+///
+/// # Examples
+///
+/// ```
+/// use async_trait::async_trait;
+/// use postal_rs::{DetailsInterest, Message, MessageHash, PostalApi, PostalError, RawMessage, SendResult};
+/// use serde_json::Value as Json;
+/// use std::collections::HashMap;
+///
+/// struct Mock;
+///
+/// #[async_trait]
+/// impl PostalApi for Mock {
+///     async fn send(&self, message: Message) -> Result<Vec<SendResult>, PostalError> {
+///         let to = message.to.unwrap_or_default();
+///         Ok(to.into_iter().map(|to| SendResult { to, id: 1, token: "token".to_owned() }).collect())
+///     }
+///
+///     async fn send_raw(&self, _: RawMessage) -> Result<Vec<SendResult>, PostalError> {
+///         Ok(Vec::new())
+///     }
+///
+///     async fn get_message_details(
+///         &self,
+///         _: DetailsInterest,
+///     ) -> Result<HashMap<String, Json>, PostalError> {
+///         Ok(HashMap::new())
+///     }
+///
+///     async fn get_message_deliveries(
+///         &self,
+///         _: MessageHash,
+///     ) -> Result<Vec<HashMap<String, Json>>, PostalError> {
+///         Ok(Vec::new())
+///     }
+/// }
+///
+/// async fn notify(api: &dyn PostalApi) -> Result<Vec<SendResult>, PostalError> {
+///     let message = Message::default()
+///         .to(&["example@gmail.com".to_owned()])
+///         .from("test@yourserver.io")
+///         .text("A test message");
+///     api.send(message).await
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let results = notify(&Mock).await.unwrap();
+///     assert_eq!(results[0].to, "example@gmail.com");
+/// }
+/// ```
+///
+/// [Client]: ./struct.Client.html
+#[async_trait]
+pub trait PostalApi: Send + Sync {
+    /// Sends a message to Postal
+    async fn send(&self, message: Message) -> Result<Vec<SendResult>, PostalError>;
+
+    /// Sends a standart SMTP message to Postal
+    async fn send_raw(&self, message: RawMessage) -> Result<Vec<SendResult>, PostalError>;
+
+    /// Asks a Postal server to provide an information details
+    /// about a message
+    async fn get_message_details(
+        &self,
+        interest: DetailsInterest,
+    ) -> Result<HashMap<String, Json>, PostalError>;
+
+    /// Obtains a delivery information according to a message.
+    async fn get_message_deliveries(
+        &self,
+        id: MessageHash,
+    ) -> Result<Vec<HashMap<String, Json>>, PostalError>;
+}
+
+#[async_trait]
+impl PostalApi for Client {
+    async fn send(&self, message: Message) -> Result<Vec<SendResult>, PostalError> {
+        Client::send(self, message).await
+    }
+
+    async fn send_raw(&self, message: RawMessage) -> Result<Vec<SendResult>, PostalError> {
+        Client::send_raw(self, message).await
+    }
+
+    async fn get_message_details(
+        &self,
+        interest: DetailsInterest,
+    ) -> Result<HashMap<String, Json>, PostalError> {
+        Client::get_message_details(self, interest).await
+    }
+
+    async fn get_message_deliveries(
+        &self,
+        id: MessageHash,
+    ) -> Result<Vec<HashMap<String, Json>>, PostalError> {
+        Client::get_message_deliveries(self, id).await
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{de, PostalError};
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use std::io::Read;
+use std::sync::Arc;
+
+/// Attachment represents a file attached to a message
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+pub struct Attachment {
+    /// The name of the file
+    #[serde(alias = "filename")]
+    pub name: String,
+    /// The MIME type of the file e.g. `application/pdf`
+    pub content_type: String,
+    /// The content of the file, it's sent base64 encoded
+    #[serde(with = "de::base64")]
+    pub data: Vec<u8>,
+    /// A Content-ID which can be referenced in an HTML body
+    /// as `cid:<content_id>`
+    ///
+    /// It's set only for inline attachments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_id: Option<String>,
+}
+
+impl Attachment {
+    /// Constructs an attachment
+    pub fn new<N, C, D>(name: N, content_type: C, data: D) -> Self
+    where
+        N: Into<String>,
+        C: Into<String>,
+        D: Into<Vec<u8>>,
+    {
+        Self {
+            name: name.into(),
+            content_type: content_type.into(),
+            data: data.into(),
+            content_id: None,
+        }
+    }
+
+    /// Constructs an attachment with a content read from a reader
+    ///
+    /// It's a convenience constructor, the whole content is read into memory.
+    /// When a message is sent the content is base64 encoded
+    /// straight into the request body without an intermediate string,
+    /// but both the content and the body are held in memory then.
+    /// So it doesn't lower the memory needed to send a large file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Attachment, Message};
+    /// use std::io::Read;
+    ///
+    /// let size = 5 * 1024 * 1024;
+    /// let report = std::io::repeat(b'a').take(size);
+    /// let attachment = Attachment::from_reader("report.pdf", "application/pdf", report).unwrap();
+    /// assert_eq!(attachment.data.len(), size as usize);
+    ///
+    /// let json = Message::default().attachment(attachment).to_json().unwrap();
+    /// let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    /// let data = base64::decode(json["attachments"][0]["data"].as_str().unwrap()).unwrap();
+    /// assert_eq!(data.len(), size as usize);
+    /// ```
+    pub fn from_reader<N, C, R>(
+        name: N,
+        content_type: C,
+        mut reader: R,
+    ) -> Result<Self, PostalError>
+    where
+        N: Into<String>,
+        C: Into<String>,
+        R: Read,
+    {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        Ok(Self::new(name, content_type, data))
+    }
+
+    /// Constructs an inline attachment e.g. an image
+    /// which is referenced in an HTML body as `<img src="cid:logo">`
+    ///
+    /// Postal doesn't support inline attachments in its JSON API,
+    /// they are sent as inline only in a message built by
+    /// [Message::into_raw_message].
+    ///
+    /// [Message::into_raw_message]: ./struct.Message.html#method.into_raw_message
+    pub fn inline<N, C, D, I>(name: N, content_type: C, data: D, content_id: I) -> Self
+    where
+        N: Into<String>,
+        C: Into<String>,
+        D: Into<Vec<u8>>,
+        I: Into<String>,
+    {
+        let content_id = content_id.into();
+        let content_id = content_id
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .to_owned();
+
+        Self {
+            content_id: Some(content_id),
+            ..Self::new(name, content_type, data)
+        }
+    }
+
+    /// Checks whether the attachment is inline
+    pub fn is_inline(&self) -> bool {
+        self.content_id.is_some()
+    }
+}
+
+/// EncodedAttachment is an attachment which content is base64 encoded once
+///
+/// A message with a large attachment sent many times e.g. in chunks
+/// would encode the attachment on each send.
+/// An encoded attachment keeps only the encoded content
+/// which is shared between its clones and messages it's added to,
+/// so it's encoded only once.
+///
+/// It can't be inline, an inline [Attachment] should be used instead.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::{Attachment, EncodedAttachment, Message};
+/// use std::sync::Arc;
+///
+/// let report = EncodedAttachment::new(Attachment::new("report.pdf", "application/pdf", "%PDF"));
+/// assert_eq!(&**report.encoded(), "JVBERg==");
+///
+/// let messages: Vec<Message> = ["a@example.com", "b@example.com"]
+///     .iter()
+///     .map(|to| {
+///         Message::default()
+///             .to(&[to.to_string()])
+///             .attachment(report.clone())
+///     })
+///     .collect();
+///
+/// // the content is shared, not encoded again
+/// assert_eq!(Arc::strong_count(report.encoded()), 3);
+///
+/// for message in &messages {
+///     let json: serde_json::Value = serde_json::from_slice(&message.to_json().unwrap()).unwrap();
+///     assert_eq!(json["attachments"][0]["data"], "JVBERg==");
+///     assert_eq!(json["attachments"][0]["name"], "report.pdf");
+/// }
+/// ```
+///
+/// [Attachment]: ./struct.Attachment.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedAttachment {
+    /// The name of the file
+    pub name: String,
+    /// The MIME type of the file e.g. `application/pdf`
+    pub content_type: String,
+    data: Arc<str>,
+}
+
+impl EncodedAttachment {
+    /// Encodes the content of an attachment
+    ///
+    /// A content ID of an inline attachment is dropped.
+    pub fn new(attachment: Attachment) -> Self {
+        Self {
+            data: base64::encode(&attachment.data).into(),
+            name: attachment.name,
+            content_type: attachment.content_type,
+        }
+    }
+
+    /// Returns the shared base64 encoded content
+    pub fn encoded(&self) -> &Arc<str> {
+        &self.data
+    }
+}
+
+impl Serialize for EncodedAttachment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("EncodedAttachment", 3)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("content_type", &self.content_type)?;
+        state.serialize_field("data", &*self.data)?;
+        state.end()
+    }
+}
+
+impl From<Attachment> for EncodedAttachment {
+    fn from(attachment: Attachment) -> Self {
+        Self::new(attachment)
+    }
+}
+
+/// MessageAttachment is an attachment of a [Message]
+///
+/// It's either an [Attachment] or an [EncodedAttachment],
+/// both are serialized the same way.
+/// A loaded message has only plain attachments.
+///
+/// [Message]: ./struct.Message.html
+/// [Attachment]: ./struct.Attachment.html
+/// [EncodedAttachment]: ./struct.EncodedAttachment.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageAttachment {
+    /// An attachment which is encoded on each send
+    Plain(Attachment),
+    /// An attachment which is encoded once
+    Encoded(EncodedAttachment),
+}
+
+impl MessageAttachment {
+    /// Returns the name of the file
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Plain(attachment) => &attachment.name,
+            Self::Encoded(attachment) => &attachment.name,
+        }
+    }
+
+    /// Returns the MIME type of the file
+    pub fn content_type(&self) -> &str {
+        match self {
+            Self::Plain(attachment) => &attachment.content_type,
+            Self::Encoded(attachment) => &attachment.content_type,
+        }
+    }
+
+    /// Returns the Content-ID of an inline attachment
+    pub fn content_id(&self) -> Option<&str> {
+        match self {
+            Self::Plain(attachment) => attachment.content_id.as_deref(),
+            Self::Encoded(_) => None,
+        }
+    }
+
+    /// Returns the length of the base64 encoded content
+    pub(crate) fn encoded_len(&self) -> usize {
+        match self {
+            Self::Plain(attachment) => attachment.data.len().div_ceil(3) * 4,
+            Self::Encoded(attachment) => attachment.data.len(),
+        }
+    }
+}
+
+impl Serialize for MessageAttachment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Plain(attachment) => attachment.serialize(serializer),
+            Self::Encoded(attachment) => attachment.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageAttachment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Attachment::deserialize(deserializer).map(Self::Plain)
+    }
+}
+
+impl From<Attachment> for MessageAttachment {
+    fn from(attachment: Attachment) -> Self {
+        Self::Plain(attachment)
+    }
+}
+
+impl From<EncodedAttachment> for MessageAttachment {
+    fn from(attachment: EncodedAttachment) -> Self {
+        Self::Encoded(attachment)
+    }
+}
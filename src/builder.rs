@@ -0,0 +1,835 @@
+use crate::hook::{SentHook, Suppression};
+use crate::{
+    Client, ContextPropagator, Endpoints, PostalError, RateLimitHeaders, Redacted, SendResult,
+    SentMessage, TokenProvider, WireProfile, API_KEY_HEADER,
+};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// ClientBuilder can be used to create a [Client] with a custom configuration
+///
+/// The API token is never printed by the `Debug` implementation.
+///
+/// [Client]: ./struct.Client.html
+#[derive(Clone)]
+pub struct ClientBuilder {
+    address: Result<Url, url::ParseError>,
+    token: String,
+    validate_addresses: bool,
+    batch_concurrency: usize,
+    key_header: String,
+    bearer_auth: bool,
+    endpoints: Endpoints,
+    wire_profile: WireProfile,
+    form_encoded: bool,
+    serialize_nulls: bool,
+    default_from: Option<String>,
+    default_reply_to: Option<String>,
+    default_tag: Option<String>,
+    max_concurrent_requests: Option<usize>,
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    on_sent: Option<SentHook>,
+    context_propagator: Option<Arc<dyn ContextPropagator>>,
+    propagate_context: bool,
+    suppression: Option<Suppression>,
+    max_message_size: Option<usize>,
+    max_response_bytes: Option<usize>,
+    rate_limit_headers: RateLimitHeaders,
+    require_sender: bool,
+    accept_invalid_hostnames: bool,
+    http2_prior_knowledge: bool,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("address", &self.address)
+            .field("token", &Redacted)
+            .field("validate_addresses", &self.validate_addresses)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("key_header", &self.key_header)
+            .field("bearer_auth", &self.bearer_auth)
+            .field("endpoints", &self.endpoints)
+            .field("wire_profile", &self.wire_profile)
+            .field("form_encoded", &self.form_encoded)
+            .field("serialize_nulls", &self.serialize_nulls)
+            .field("default_from", &self.default_from)
+            .field("default_reply_to", &self.default_reply_to)
+            .field("default_tag", &self.default_tag)
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .field("token_provider", &self.token_provider.as_ref().map(|_| ..))
+            .field("on_sent", &self.on_sent.as_ref().map(|_| ..))
+            .field(
+                "context_propagator",
+                &self.context_propagator.as_ref().map(|_| ..),
+            )
+            .field("propagate_context", &self.propagate_context)
+            .field("suppression", &self.suppression.as_ref().map(|_| ..))
+            .field("max_message_size", &self.max_message_size)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("rate_limit_headers", &self.rate_limit_headers)
+            .field("require_sender", &self.require_sender)
+            .field("accept_invalid_hostnames", &self.accept_invalid_hostnames)
+            .field("http2_prior_knowledge", &self.http2_prior_knowledge)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .finish()
+    }
+}
+
+impl ClientBuilder {
+    /// Constructs a new builder with a default configuration
+    pub fn new<U, S>(url: U, token: S) -> Self
+    where
+        U: AsRef<str>,
+        S: Into<String>,
+    {
+        Self::with_address(parse_url(url.as_ref()), token.into())
+    }
+
+    /// Constructs a new builder with a default configuration
+    /// from an already parsed URL
+    pub fn from_url<S: Into<String>>(url: Url, token: S) -> Self {
+        Self::with_address(Ok(url), token.into())
+    }
+
+    fn with_address(address: Result<Url, url::ParseError>, token: String) -> Self {
+        Self {
+            address,
+            token,
+            validate_addresses: false,
+            batch_concurrency: 10,
+            key_header: API_KEY_HEADER.to_owned(),
+            bearer_auth: false,
+            endpoints: Endpoints::default(),
+            wire_profile: WireProfile::default(),
+            form_encoded: false,
+            serialize_nulls: false,
+            default_from: None,
+            default_reply_to: None,
+            default_tag: None,
+            max_concurrent_requests: None,
+            token_provider: None,
+            on_sent: None,
+            context_propagator: default_propagator(),
+            propagate_context: true,
+            suppression: None,
+            max_message_size: None,
+            max_response_bytes: None,
+            rate_limit_headers: RateLimitHeaders::default(),
+            require_sender: false,
+            accept_invalid_hostnames: false,
+            http2_prior_knowledge: false,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+        }
+    }
+
+    /// Turns on validation of addresses of each message before it's sent
+    ///
+    /// It's off by default.
+    /// See [Message::validate_addresses].
+    ///
+    /// [Message::validate_addresses]: ./struct.Message.html#method.validate_addresses
+    pub fn validate_addresses(mut self, validate: bool) -> Self {
+        self.validate_addresses = validate;
+        self
+    }
+
+    /// Sets how many requests a single batch method can run concurrently
+    ///
+    /// It's 10 by default.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `limit` is 0.
+    pub fn batch_concurrency(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "batch concurrency must be positive");
+        self.batch_concurrency = limit;
+        self
+    }
+
+    /// Sets a name of a header the API key is sent in
+    ///
+    /// It's [API_KEY_HEADER] by default.
+    /// It may be useful when a proxy in front of Postal expects
+    /// the key in a different header.
+    ///
+    /// [API_KEY_HEADER]: ./constant.API_KEY_HEADER.html
+    pub fn api_key_header<S: Into<String>>(mut self, name: S) -> Self {
+        self.key_header = name.into();
+        self
+    }
+
+    /// Sends the API key in an `Authorization: Bearer` header as well
+    ///
+    /// It's off by default.
+    pub fn bearer_auth(mut self, on: bool) -> Self {
+        self.bearer_auth = on;
+        self
+    }
+
+    /// Overrides paths of API methods
+    ///
+    /// It may be useful for deployments which
+    /// don't use the standard paths.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Sets names of keys a message is sent with
+    ///
+    /// It's [WireProfile::Postal] by default.
+    ///
+    /// [WireProfile::Postal]: ./enum.WireProfile.html#variant.Postal
+    pub fn wire_profile(mut self, profile: WireProfile) -> Self {
+        self.wire_profile = profile;
+        self
+    }
+
+    /// Sends messages as `application/x-www-form-urlencoded` fields instead of JSON
+    ///
+    /// It's off by default.
+    /// It may be useful for legacy deployments which reject JSON.
+    /// Arrays are sent as `to[]=a&to[]=b`
+    /// and attachments as `attachments[][name]=a.txt`.
+    /// Only sending of messages is affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use postal_rs::{Client, Message};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let client = Client::builder(mock.url(), MOCK_TOKEN)
+    ///         .form_encoded(true)
+    ///         .build()
+    ///         .unwrap();
+    ///     let message = Message::default()
+    ///         .from("Sender <sender@example.com>")
+    ///         .to(&["a@example.com".to_owned(), "b@example.com".to_owned()])
+    ///         .subject("Hi")
+    ///         .text("Hello there");
+    ///     client.send(message).await.unwrap();
+    ///
+    ///     let request = &mock.requests()[0];
+    ///     assert_eq!(
+    ///         request.header("content-type"),
+    ///         Some("application/x-www-form-urlencoded")
+    ///     );
+    ///     assert_eq!(
+    ///         String::from_utf8_lossy(&request.body),
+    ///         "from=Sender+%3Csender%40example.com%3E\
+    ///          &plain_body=Hello+there\
+    ///          &subject=Hi\
+    ///          &to%5B%5D=a%40example.com&to%5B%5D=b%40example.com"
+    ///     );
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub fn form_encoded(mut self, on: bool) -> Self {
+        self.form_encoded = on;
+        self
+    }
+
+    /// Sends fields of a message which are not set as `null`
+    ///
+    /// It's off by default, so such fields are omitted
+    /// as some proxies reject `null` values.
+    /// Form encoded messages never contain them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use postal_rs::{Client, Message};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///
+    ///     mock.client().send(message.clone()).await.unwrap();
+    ///
+    ///     let client = Client::builder(mock.url(), MOCK_TOKEN)
+    ///         .serialize_nulls(true)
+    ///         .build()
+    ///         .unwrap();
+    ///     client.send(message).await.unwrap();
+    ///
+    ///     let requests = mock.requests();
+    ///     assert!(requests[0].json().unwrap().get("subject").is_none());
+    ///     assert!(requests[1].json().unwrap()["subject"].is_null());
+    ///     assert_eq!(requests[1].json().unwrap()["plain_body"], "A test message");
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub fn serialize_nulls(mut self, on: bool) -> Self {
+        self.serialize_nulls = on;
+        self
+    }
+
+    /// Sets a `from` address of messages which don't have one
+    ///
+    /// A `from` address set on a message is never overridden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, Message};
+    ///
+    /// let client = Client::builder("https://postal.example.com", "token")
+    ///     .default_from("noreply@yourserver.io")
+    ///     .build()
+    ///     .unwrap();
+    /// let message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .text("A test message");
+    ///
+    /// let sent = client.send_dry_run(message.clone()).unwrap();
+    /// assert_eq!(sent.from.as_deref(), Some("noreply@yourserver.io"));
+    ///
+    /// let sent = client.send_dry_run(message.from("test@yourserver.io")).unwrap();
+    /// assert_eq!(sent.from.as_deref(), Some("test@yourserver.io"));
+    /// ```
+    pub fn default_from<S: Into<String>>(mut self, from: S) -> Self {
+        self.default_from = Some(from.into());
+        self
+    }
+
+    /// Sets a `reply_to` address of messages which don't have one
+    ///
+    /// A `reply_to` address set on a message is never overridden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, Message};
+    ///
+    /// let client = Client::builder("https://postal.example.com", "token")
+    ///     .default_reply_to("support@yourserver.io")
+    ///     .build()
+    ///     .unwrap();
+    /// let mut message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .from("test@yourserver.io");
+    ///
+    /// let sent = client.send_dry_run(message.clone()).unwrap();
+    /// assert_eq!(sent.reply_to.as_deref(), Some("support@yourserver.io"));
+    ///
+    /// message.reply_to = Some("team@yourserver.io".to_owned());
+    /// let sent = client.send_dry_run(message).unwrap();
+    /// assert_eq!(sent.reply_to.as_deref(), Some("team@yourserver.io"));
+    /// ```
+    pub fn default_reply_to<S: Into<String>>(mut self, reply_to: S) -> Self {
+        self.default_reply_to = Some(reply_to.into());
+        self
+    }
+
+    /// Sets a provider which is asked for a token on each request
+    ///
+    /// The token given to the builder is not used then.
+    pub fn token_provider<P: TokenProvider + 'static>(mut self, provider: P) -> Self {
+        self.token_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Sets a tag of messages which don't have one
+    ///
+    /// A tag set on a message is never overridden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, Message};
+    ///
+    /// let client = Client::builder("https://postal.example.com", "token")
+    ///     .default_tag("billing")
+    ///     .build()
+    ///     .unwrap();
+    /// let mut message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .from("test@yourserver.io");
+    ///
+    /// let sent = client.send_dry_run(message.clone()).unwrap();
+    /// assert_eq!(sent.tag.as_deref(), Some("billing"));
+    ///
+    /// message.tag = Some("invoices".to_owned());
+    /// let sent = client.send_dry_run(message).unwrap();
+    /// assert_eq!(sent.tag.as_deref(), Some("invoices"));
+    /// ```
+    pub fn default_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.default_tag = Some(tag.into());
+        self
+    }
+
+    /// Sets a hook which is called after each message is sent successfully
+    ///
+    /// It's called by [Client::send], [Client::send_raw] and their variants
+    /// with the message as it was sent and the results of sending.
+    /// It may be used e.g. to keep an audit log.
+    ///
+    /// The hook is called within a send future,
+    /// so it must not block for long, otherwise it blocks the executor.
+    /// A slow work like writing to a database should be handed over
+    /// e.g. through a channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use postal_rs::{Client, Message, SentMessage};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let audit = Arc::new(Mutex::new(Vec::new()));
+    ///     let log = audit.clone();
+    ///     let client = Client::builder(mock.url(), MOCK_TOKEN)
+    ///         .on_sent(move |message, results| {
+    ///             if let SentMessage::Message(message) = message {
+    ///                 log.lock().unwrap().push((message.subject.clone(), results.to_vec()));
+    ///             }
+    ///         })
+    ///         .build()
+    ///         .unwrap();
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .subject("Hello World")
+    ///         .text("A test message");
+    ///
+    ///     let results = client.send(message).await.unwrap();
+    ///
+    ///     let audit = audit.lock().unwrap();
+    ///     assert_eq!(audit.len(), 1);
+    ///     assert_eq!(audit[0].0.as_deref(), Some("Hello World"));
+    ///     assert_eq!(audit[0].1, results);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [Client::send]: ./struct.Client.html#method.send
+    /// [Client::send_raw]: ./struct.Client.html#method.send_raw
+    pub fn on_sent<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(SentMessage<'_>, &[SendResult]) + Send + Sync + 'static,
+    {
+        self.on_sent = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a check of addresses which must never get a message e.g. opt-outs
+    ///
+    /// Suppressed recipients are removed from a message before it's sent,
+    /// see [Message::remove_suppressed].
+    /// If all recipients are suppressed the message is not sent
+    /// and [PostalError::AllRecipientsSuppressed] is returned.
+    /// Raw messages are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, Message, PostalError};
+    ///
+    /// let client = Client::builder("https://postal.example.com", "token")
+    ///     .suppression(|to: &str| to.ends_with("@optout.io"))
+    ///     .build()
+    ///     .unwrap();
+    /// let message = Message::default()
+    ///     .from("test@yourserver.io")
+    ///     .text("A test message");
+    ///
+    /// let partial = message
+    ///     .clone()
+    ///     .to(&["a@gmail.com".to_owned(), "b@optout.io".to_owned()]);
+    /// let sent = client.send_dry_run(partial).unwrap();
+    /// assert_eq!(sent.to.unwrap(), ["a@gmail.com"]);
+    ///
+    /// let full = message.to(&["b@optout.io".to_owned()]);
+    /// assert!(matches!(
+    ///     client.send_dry_run(full),
+    ///     Err(PostalError::AllRecipientsSuppressed(suppressed)) if suppressed == ["b@optout.io"]
+    /// ));
+    /// ```
+    ///
+    /// [Message::remove_suppressed]: ./struct.Message.html#method.remove_suppressed
+    /// [PostalError::AllRecipientsSuppressed]: ./enum.PostalError.html#variant.AllRecipientsSuppressed
+    pub fn suppression<F>(mut self, is_suppressed: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.suppression = Some(Arc::new(is_suppressed));
+        self
+    }
+
+    /// Limits a size of a message which can be sent
+    ///
+    /// A larger message fails with [PostalError::MessageTooLarge]
+    /// without being sent, its size is estimated by [Message::estimated_size].
+    /// It's unlimited by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Attachment, Client, Message, PostalError};
+    ///
+    /// let client = Client::builder("https://postal.example.com", "token")
+    ///     .max_message_size(1024)
+    ///     .build()
+    ///     .unwrap();
+    /// let message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .from("test@yourserver.io")
+    ///     .text("A test message");
+    /// assert!(client.send_dry_run(message.clone()).is_ok());
+    ///
+    /// let message = message.attachment(Attachment::new("a.pdf", "application/pdf", vec![0; 1024]));
+    /// assert!(matches!(
+    ///     client.send_dry_run(message),
+    ///     Err(PostalError::MessageTooLarge { limit: 1024, .. })
+    /// ));
+    /// ```
+    ///
+    /// [PostalError::MessageTooLarge]: ./enum.PostalError.html#variant.MessageTooLarge
+    /// [Message::estimated_size]: ./struct.Message.html#method.estimated_size
+    pub fn max_message_size(mut self, limit: usize) -> Self {
+        self.max_message_size = Some(limit);
+        self
+    }
+
+    /// Limits a size of a response body which is read from Postal
+    ///
+    /// The body is read in chunks and reading stops
+    /// with [PostalError::ResponseTooLarge] as soon as it exceeds the limit,
+    /// so e.g. a huge `raw_message` expansion is never buffered.
+    /// It's unlimited by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use postal_rs::{Client, PostalError};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     mock.respond_success(
+    ///         "/api/v1/messages/message",
+    ///         serde_json::json!({
+    ///             "id": 1,
+    ///             "token": "abc",
+    ///             "raw_message": base64::encode(vec![b'a'; 64 * 1024]),
+    ///         }),
+    ///     );
+    ///     let client = Client::builder(mock.url(), MOCK_TOKEN)
+    ///         .max_response_bytes(1024)
+    ///         .build()
+    ///         .unwrap();
+    ///
+    ///     assert!(matches!(
+    ///         client.get_raw_message(1).await,
+    ///         Err(PostalError::ResponseTooLarge { limit: 1024 })
+    ///     ));
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [PostalError::ResponseTooLarge]: ./enum.PostalError.html#variant.ResponseTooLarge
+    pub fn max_response_bytes(mut self, limit: usize) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+
+    /// Sets names of headers a rate limit is read from
+    ///
+    /// They're `X-RateLimit-Limit`, `X-RateLimit-Remaining`
+    /// and `X-RateLimit-Reset` by default.
+    /// See [Client::rate_limit].
+    ///
+    /// [Client::rate_limit]: ./struct.Client.html#method.rate_limit
+    pub fn rate_limit_headers(mut self, headers: RateLimitHeaders) -> Self {
+        self.rate_limit_headers = headers;
+        self
+    }
+
+    /// Refuses to send a message which has several `from` addresses
+    /// but no `sender`
+    ///
+    /// It's off by default, such a message is only logged
+    /// with the `tracing` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, Message, PostalError};
+    ///
+    /// let client = Client::builder("https://postal.example.com", "token")
+    ///     .require_sender(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .from("alice@yourserver.io, bob@yourserver.io")
+    ///     .text("A test message");
+    ///
+    /// assert!(matches!(
+    ///     client.send_dry_run(message.clone()),
+    ///     Err(PostalError::InvalidMessage(_))
+    /// ));
+    ///
+    /// let message = message.sender("alice@yourserver.io");
+    /// let json: serde_json::Value = serde_json::from_slice(&client.send_dry_run(message).unwrap().to_json().unwrap()).unwrap();
+    /// assert_eq!(json["from"], "alice@yourserver.io, bob@yourserver.io");
+    /// assert_eq!(json["sender"], "alice@yourserver.io");
+    /// ```
+    pub fn require_sender(mut self, require: bool) -> Self {
+        self.require_sender = require;
+        self
+    }
+
+    /// Sets a propagator which adds a trace context to each request
+    ///
+    /// It's not set by default, so no trace context is sent.
+    /// With the `otel` feature [OtelPropagator] is set by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use postal_rs::{traceparent, Client, Message, TRACEPARENT_HEADER};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let parent = traceparent(0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7, true);
+    ///     let context = parent.clone();
+    ///     let client = Client::builder(mock.url(), MOCK_TOKEN)
+    ///         .context_propagator(move || vec![(TRACEPARENT_HEADER.to_owned(), context.clone())])
+    ///         .build()
+    ///         .unwrap();
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///
+    ///     client.send(message).await.unwrap();
+    ///
+    ///     assert_eq!(mock.requests()[0].header(TRACEPARENT_HEADER), Some(parent.as_str()));
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [OtelPropagator]: ./struct.OtelPropagator.html
+    pub fn context_propagator<P: ContextPropagator + 'static>(mut self, propagator: P) -> Self {
+        self.context_propagator = Some(Arc::new(propagator));
+        self
+    }
+
+    /// Sets whether a trace context is added to requests
+    ///
+    /// It's enabled by default.
+    /// Disabling it turns off a propagator set by [context_propagator]
+    /// as well as the default one of the `otel` feature.
+    ///
+    /// [context_propagator]: #method.context_propagator
+    pub fn propagate_context(mut self, propagate: bool) -> Self {
+        self.propagate_context = propagate;
+        self
+    }
+
+    /// Limits how many requests the client and all its clones
+    /// can send to Postal at the same time
+    ///
+    /// Requests above the limit wait until a running one completes.
+    /// It's unlimited by default.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `limit` is 0.
+    pub fn max_concurrent_requests(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "a limit of concurrent requests must be positive");
+        self.max_concurrent_requests = Some(limit);
+        self
+    }
+
+    /// Turns off checking that a TLS certificate of Postal
+    /// is issued for the host of the base URL
+    ///
+    /// The certificate chain is still validated.
+    /// It's off by default.
+    ///
+    /// # Warning
+    ///
+    /// It's dangerous, any valid certificate is accepted for any host,
+    /// which makes the client open to man-in-the-middle attacks.
+    /// Use it only when a certificate of Postal can't match the URL
+    /// it's accessed by, e.g. with split-horizon DNS.
+    pub fn danger_accept_invalid_hostnames(mut self, accept: bool) -> Self {
+        self.accept_invalid_hostnames = accept;
+        self
+    }
+
+    /// Sends requests over HTTP/2 without negotiating it
+    ///
+    /// It's mostly useful for a plain `http` URL of a Postal which supports HTTP/2,
+    /// over TLS HTTP/2 is negotiated anyway if Postal supports it.
+    /// Requests fail if Postal doesn't support HTTP/2.
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Sets how many idle connections to Postal are kept open
+    ///
+    /// It's unlimited by default.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Sets how long an idle connection to Postal is kept open
+    ///
+    /// It's 90 seconds by default.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Builds a client
+    ///
+    /// It fails with [PostalError::MissingToken] if the token is blank
+    /// and there's no [token_provider].
+    ///
+    /// [PostalError::MissingToken]: ./enum.PostalError.html#variant.MissingToken
+    /// [token_provider]: #method.token_provider
+    pub fn build(self) -> Result<Client, PostalError> {
+        if self.token_provider.is_none() && self.token.trim().is_empty() {
+            return Err(PostalError::MissingToken);
+        }
+
+        let address = base_url(self.address?)?;
+
+        let mut http = reqwest::Client::builder()
+            .danger_accept_invalid_hostnames(self.accept_invalid_hostnames);
+        if self.http2_prior_knowledge {
+            http = http.http2_prior_knowledge();
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            http = http.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            http = http.pool_idle_timeout(timeout);
+        }
+
+        let propagate_context = self.propagate_context;
+        Ok(Client {
+            address,
+            token: self.token,
+            validate_addresses: self.validate_addresses,
+            batch_concurrency: self.batch_concurrency,
+            key_header: self.key_header,
+            bearer_auth: self.bearer_auth,
+            endpoints: self.endpoints,
+            wire_profile: self.wire_profile,
+            form_encoded: self.form_encoded,
+            serialize_nulls: self.serialize_nulls,
+            default_from: self.default_from,
+            default_reply_to: self.default_reply_to,
+            default_tag: self.default_tag,
+            limit: self
+                .max_concurrent_requests
+                .map(|limit| Arc::new(Semaphore::new(limit))),
+            in_flight: Arc::default(),
+            token_provider: self.token_provider,
+            on_sent: self.on_sent,
+            context_propagator: self.context_propagator.filter(|_| propagate_context),
+            suppression: self.suppression,
+            max_message_size: self.max_message_size,
+            max_response_bytes: self.max_response_bytes,
+            rate_limit_headers: self.rate_limit_headers,
+            rate_limit: Arc::default(),
+            require_sender: self.require_sender,
+            http: http.build()?,
+        })
+    }
+}
+
+#[cfg(feature = "otel")]
+fn default_propagator() -> Option<Arc<dyn ContextPropagator>> {
+    Some(Arc::new(crate::OtelPropagator))
+}
+
+#[cfg(not(feature = "otel"))]
+fn default_propagator() -> Option<Arc<dyn ContextPropagator>> {
+    None
+}
+
+/// Parses a URL of Postal
+///
+/// A URL without a scheme e.g. `postal.example.com:8080` gets `https://`.
+pub(crate) fn parse_url(url: &str) -> Result<Url, url::ParseError> {
+    let url = url.trim();
+    if has_scheme(url) {
+        Url::parse(url)
+    } else {
+        Url::parse(&format!("https://{}", url))
+    }
+}
+
+/// Checks that a URL starts with a scheme followed by `://`
+fn has_scheme(url: &str) -> bool {
+    let scheme = match url.find("://") {
+        Some(end) => &url[..end],
+        None => return false,
+    };
+
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Checks a base URL of Postal
+pub(crate) fn base_url(mut address: Url) -> Result<Url, PostalError> {
+    if address.host().is_none() {
+        return Err(PostalError::UrlIssue(url::ParseError::EmptyHost));
+    }
+
+    // a path without a trailing slash would lose its last segment
+    // while joining an endpoint path
+    if !address.path().ends_with('/') {
+        let path = format!("{}/", address.path());
+        address.set_path(&path);
+    }
+
+    Ok(address)
+}
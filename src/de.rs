@@ -0,0 +1,55 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value as Json;
+
+/// Postal returns some flags as `0`/`1` and some as booleans.
+pub(crate) fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let value = Json::deserialize(deserializer)?;
+    match value {
+        Json::Bool(b) => Ok(b),
+        Json::Number(n) => Ok(n.as_f64() != Some(0.0)),
+        _ => Ok(false),
+    }
+}
+
+/// Postal returns some numbers as strings.
+pub(crate) fn number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let value = Json::deserialize(deserializer)?;
+    match value {
+        Json::Number(n) => Ok(n.as_u64()),
+        Json::String(s) => Ok(s.parse().ok()),
+        _ => Ok(None),
+    }
+}
+
+/// Converts a unix timestamp with a fractional part of seconds into a date.
+#[cfg(feature = "chrono")]
+pub(crate) fn timestamp(timestamp: f64) -> Option<chrono::DateTime<chrono::Utc>> {
+    if !timestamp.is_finite() {
+        return None;
+    }
+
+    let secs = timestamp.floor();
+    let nanos = ((timestamp - secs) * 1e9).round().min(999_999_999.0);
+    chrono::DateTime::from_timestamp(secs as i64, nanos as u32)
+}
+
+/// Serializes bytes as a base64 string.
+///
+/// The data is encoded while it's written
+/// so the encoded string is never allocated at once.
+pub(crate) mod base64 {
+    use ::base64::display::Base64Display;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Base64Display::with_config(data, ::base64::STANDARD))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let data = String::deserialize(deserializer)?;
+        let data: String = data.split_whitespace().collect();
+        ::base64::decode(data).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,140 @@
+use crate::de::flag;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Delivery represents an attempt to deliver a message
+///
+/// The SMTP reply code and the enhanced status code are parsed
+/// from the output of the remote server.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::Delivery;
+///
+/// let parse = |output: &str| -> Delivery {
+///     serde_json::from_value(serde_json::json!({
+///         "id": 1,
+///         "status": "HardFail",
+///         "output": output,
+///         "timestamp": 1600000000.0,
+///     }))
+///     .unwrap()
+/// };
+///
+/// let delivery = parse("550 5.1.1 User unknown");
+/// assert_eq!(delivery.smtp_code, Some(550));
+/// assert_eq!(delivery.enhanced_status.as_deref(), Some("5.1.1"));
+/// assert_eq!(delivery.output.as_deref(), Some("550 5.1.1 User unknown"));
+///
+/// let delivery = parse("550-5.1.1 The email account that you tried to reach does not exist.");
+/// assert_eq!(delivery.smtp_code, Some(550));
+/// assert_eq!(delivery.enhanced_status.as_deref(), Some("5.1.1"));
+///
+/// let delivery = parse("host mx.example.com said: 452 4.2.2 Mailbox full (in reply to RCPT TO)");
+/// assert_eq!(delivery.smtp_code, Some(452));
+/// assert_eq!(delivery.enhanced_status.as_deref(), Some("4.2.2"));
+///
+/// let delivery = parse("554 Message rejected");
+/// assert_eq!(delivery.smtp_code, Some(554));
+/// assert_eq!(delivery.enhanced_status, None);
+///
+/// let delivery = parse("Connection refused");
+/// assert_eq!(delivery.smtp_code, None);
+/// assert_eq!(delivery.enhanced_status, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(remote = "Self")]
+pub struct Delivery {
+    /// The id of the delivery
+    pub id: u64,
+    /// The status of the delivery e.g. `Sent` or `SoftFail`
+    pub status: String,
+    /// A human readable description of the delivery
+    #[serde(default)]
+    pub details: Option<String>,
+    /// An output of the remote server
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Was the message sent over a TLS connection?
+    #[serde(default, deserialize_with = "flag")]
+    pub sent_with_ssl: bool,
+    /// An id of a log entry related to the delivery
+    #[serde(default)]
+    pub log_id: Option<String>,
+    /// How long the delivery took in seconds
+    #[serde(default)]
+    pub time: Option<f64>,
+    /// A unix timestamp of the delivery
+    pub timestamp: f64,
+    /// The SMTP reply code from the output e.g. `550`
+    #[serde(default)]
+    pub smtp_code: Option<u16>,
+    /// The enhanced status code from the output e.g. `5.1.1`
+    #[serde(default)]
+    pub enhanced_status: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for Delivery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut delivery = Delivery::deserialize(deserializer)?;
+        if let Some(output) = &delivery.output {
+            let (smtp_code, enhanced_status) = diagnostic(output);
+            delivery.smtp_code = delivery.smtp_code.or(smtp_code);
+            delivery.enhanced_status = delivery.enhanced_status.or(enhanced_status);
+        }
+
+        Ok(delivery)
+    }
+}
+
+impl Serialize for Delivery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Delivery::serialize(self, serializer)
+    }
+}
+
+impl Delivery {
+    /// Returns the time of the delivery
+    #[cfg(feature = "chrono")]
+    pub fn timestamp_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        crate::de::timestamp(self.timestamp)
+    }
+}
+
+/// Finds an SMTP reply code and an enhanced status code in a diagnostic
+///
+/// The reply code is the first 3 digit number beginning with 2, 4 or 5,
+/// the enhanced status code is the first `class.subject.detail` triple.
+fn diagnostic(output: &str) -> (Option<u16>, Option<String>) {
+    let words = output
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric()));
+
+    let mut smtp_code = None;
+    let mut enhanced_status = None;
+    for word in words {
+        if smtp_code.is_none() && is_reply_code(word) {
+            smtp_code = word.parse().ok();
+        }
+        if enhanced_status.is_none() && is_enhanced_status(word) {
+            enhanced_status = Some(word.to_owned());
+        }
+    }
+
+    (smtp_code, enhanced_status)
+}
+
+fn is_reply_code(word: &str) -> bool {
+    word.len() == 3
+        && word.bytes().all(|b| b.is_ascii_digit())
+        && matches!(word.as_bytes()[0], b'2' | b'4' | b'5')
+}
+
+fn is_enhanced_status(word: &str) -> bool {
+    let parts: Vec<&str> = word.split('.').collect();
+    parts.len() == 3
+        && matches!(parts[0], "2" | "4" | "5")
+        && parts[1..]
+            .iter()
+            .all(|part| (1..=3).contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit()))
+}
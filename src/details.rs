@@ -0,0 +1,354 @@
+use crate::de::{flag, number};
+use crate::MessageHash;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use std::collections::HashMap;
+
+/// MessageDetails represents an information about a message
+///
+/// Each optional field is set only when a corresponding
+/// expansion was requested via [DetailsInterest].
+///
+/// [DetailsInterest]: ./struct.DetailsInterest.html
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageDetails {
+    /// The id of the message
+    pub id: MessageHash,
+    /// The token of the message
+    pub token: String,
+    /// The status expansion
+    #[serde(default)]
+    pub status: Option<MessageStatusInfo>,
+    /// The details expansion
+    #[serde(default)]
+    pub details: Option<MessageInfo>,
+    /// The inspection expansion
+    #[serde(default)]
+    pub inspection: Option<Inspection>,
+    /// The plain text body of the message
+    #[serde(default)]
+    pub plain_body: Option<String>,
+    /// The HTML body of the message
+    #[serde(default)]
+    pub html_body: Option<String>,
+    /// The attachments of the message
+    #[serde(default)]
+    pub attachments: Option<Vec<Json>>,
+    /// The headers of the message
+    #[serde(default)]
+    pub headers: Option<HashMap<String, Vec<String>>>,
+    /// A base64 encoded raw message
+    #[serde(default)]
+    pub raw_message: Option<String>,
+}
+
+impl MessageDetails {
+    /// Returns the first value of a header from the headers expansion
+    ///
+    /// The name is compared case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::MessageDetails;
+    ///
+    /// let details: MessageDetails = serde_json::from_str(
+    ///     r#"{
+    ///         "id": 1,
+    ///         "token": "abcdef",
+    ///         "headers": {
+    ///             "message-id": ["<1@example.com>"],
+    ///             "dkim-signature": ["v=1; d=example.com"],
+    ///             "x-postal-msgid": ["abc"],
+    ///             "x-postal-tag": ["spring"]
+    ///         }
+    ///     }"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(details.header("Message-ID"), Some("<1@example.com>"));
+    /// assert_eq!(details.header("DKIM-Signature"), Some("v=1; d=example.com"));
+    /// assert_eq!(details.header("Subject"), None);
+    ///
+    /// let mut postal = details.headers_matching("X-Postal-");
+    /// postal.sort();
+    /// assert_eq!(
+    ///     postal,
+    ///     vec![("x-postal-msgid", "abc"), ("x-postal-tag", "spring")]
+    /// );
+    /// ```
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .as_ref()?
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .and_then(|(_, values)| values.first())
+            .map(String::as_str)
+    }
+
+    /// Returns values of headers which names start with a prefix
+    ///
+    /// The prefix is compared case-insensitively.
+    /// A header with several values is returned once per value.
+    pub fn headers_matching(&self, prefix: &str) -> Vec<(&str, &str)> {
+        let prefix = prefix.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .flatten()
+            .filter(|(name, _)| name.to_ascii_lowercase().starts_with(&prefix))
+            .flat_map(|(name, values)| {
+                values
+                    .iter()
+                    .map(move |value| (name.as_str(), value.as_str()))
+            })
+            .collect()
+    }
+}
+
+/// MessageStatusInfo contains a status of a message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageStatusInfo {
+    /// The status of the message
+    pub status: MessageStatus,
+    /// A unix timestamp of the last delivery attempt
+    #[serde(default)]
+    pub last_delivery_attempt: Option<f64>,
+    /// Is the message held?
+    #[serde(default, deserialize_with = "flag")]
+    pub held: bool,
+    /// A unix timestamp when the hold expires
+    #[serde(default)]
+    pub hold_expiry: Option<f64>,
+}
+
+impl MessageStatusInfo {
+    /// Returns the time of the last delivery attempt
+    #[cfg(feature = "chrono")]
+    pub fn last_delivery_attempt_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_delivery_attempt.and_then(crate::de::timestamp)
+    }
+
+    /// Returns the time when the hold expires
+    #[cfg(feature = "chrono")]
+    pub fn hold_expiry_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.hold_expiry.and_then(crate::de::timestamp)
+    }
+}
+
+/// MessageStatus is an overall state of a message
+///
+/// A status which is not known is kept in [Unknown].
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::MessageStatus;
+///
+/// let statuses: Vec<MessageStatus> = serde_json::from_str(
+///     r#"["Pending", "Processed", "Sent", "SoftFail", "HardFail", "Held", "Bounced", "MXError"]"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     statuses,
+///     vec![
+///         MessageStatus::Pending,
+///         MessageStatus::Processed,
+///         MessageStatus::Sent,
+///         MessageStatus::SoftFail,
+///         MessageStatus::HardFail,
+///         MessageStatus::Held,
+///         MessageStatus::Bounced,
+///         MessageStatus::Unknown("MXError".to_owned()),
+///     ]
+/// );
+/// assert_eq!(serde_json::to_string(&MessageStatus::SoftFail).unwrap(), r#""SoftFail""#);
+/// ```
+///
+/// [Unknown]: #variant.Unknown
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum MessageStatus {
+    /// The message is waiting to be processed
+    Pending,
+    /// The message was processed
+    Processed,
+    /// The message was delivered
+    Sent,
+    /// A delivery failed temporarily and will be retried
+    SoftFail,
+    /// A delivery failed permanently
+    HardFail,
+    /// The message is held
+    Held,
+    /// The message bounced
+    Bounced,
+    /// Any other status
+    Unknown(String),
+}
+
+impl MessageStatus {
+    /// Checks whether the status won't change anymore
+    ///
+    /// It's true for [Sent], [HardFail] and [Bounced].
+    ///
+    /// [Sent]: #variant.Sent
+    /// [HardFail]: #variant.HardFail
+    /// [Bounced]: #variant.Bounced
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Sent | Self::HardFail | Self::Bounced)
+    }
+}
+
+impl From<String> for MessageStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "Pending" => Self::Pending,
+            "Processed" => Self::Processed,
+            "Sent" => Self::Sent,
+            "SoftFail" => Self::SoftFail,
+            "HardFail" => Self::HardFail,
+            "Held" => Self::Held,
+            "Bounced" => Self::Bounced,
+            _ => Self::Unknown(status),
+        }
+    }
+}
+
+impl From<MessageStatus> for String {
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::Pending => "Pending".to_owned(),
+            MessageStatus::Processed => "Processed".to_owned(),
+            MessageStatus::Sent => "Sent".to_owned(),
+            MessageStatus::SoftFail => "SoftFail".to_owned(),
+            MessageStatus::HardFail => "HardFail".to_owned(),
+            MessageStatus::Held => "Held".to_owned(),
+            MessageStatus::Bounced => "Bounced".to_owned(),
+            MessageStatus::Unknown(status) => status,
+        }
+    }
+}
+
+/// MessageInfo contains general details of a message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageInfo {
+    /// The recipient of the message
+    #[serde(default)]
+    pub rcpt_to: Option<String>,
+    /// The envelope sender of the message
+    #[serde(default)]
+    pub mail_from: Option<String>,
+    /// The subject of the message
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// The value of the `Message-ID` header
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// A unix timestamp when the message was received
+    #[serde(default)]
+    pub timestamp: Option<f64>,
+    /// Either `incoming` or `outgoing`
+    #[serde(default)]
+    pub direction: Option<String>,
+    /// The size of the message in bytes
+    #[serde(default, deserialize_with = "number")]
+    pub size: Option<u64>,
+    /// Is this message a bounce?
+    #[serde(default, deserialize_with = "flag")]
+    pub bounce: bool,
+    /// The id of a message this message is a bounce for
+    #[serde(default)]
+    pub bounce_for_id: Option<MessageHash>,
+    /// The tag of the message
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Was the message received over a TLS connection?
+    #[serde(default, deserialize_with = "flag")]
+    pub received_with_ssl: bool,
+}
+
+impl MessageInfo {
+    /// Returns the time when the message was received
+    #[cfg(feature = "chrono")]
+    pub fn timestamp_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.timestamp.and_then(crate::de::timestamp)
+    }
+}
+
+/// Inspection contains results of spam and threat checks of a message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Inspection {
+    /// Was the message inspected?
+    #[serde(default, deserialize_with = "flag")]
+    pub inspected: bool,
+    /// Is the message considered a spam?
+    #[serde(default, deserialize_with = "flag")]
+    pub spam: bool,
+    /// The spam score of the message
+    #[serde(default)]
+    pub spam_score: f64,
+    /// Is the message considered a threat?
+    #[serde(default, deserialize_with = "flag")]
+    pub threat: bool,
+    /// The details of a found threat
+    #[serde(default)]
+    pub threat_details: Option<String>,
+    /// The spam checks which matched the message
+    #[serde(default)]
+    pub spam_checks: Vec<SpamCheck>,
+}
+
+impl Inspection {
+    /// Returns the sum of scores of the spam checks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Inspection;
+    ///
+    /// let inspection: Inspection = serde_json::from_str(
+    ///     r#"{
+    ///         "inspected": true,
+    ///         "spam": false,
+    ///         "spam_score": 2.5,
+    ///         "threat": false,
+    ///         "spam_checks": [
+    ///             { "code": "HTML_MESSAGE", "score": 0.5, "description": "HTML included in message" },
+    ///             { "code": "MISSING_DATE", "score": 2.0, "description": "Missing Date: header" }
+    ///         ]
+    ///     }"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(inspection.spam_checks[1].code, "MISSING_DATE");
+    /// assert_eq!(inspection.total_score(), 2.5);
+    /// assert!(inspection.is_spam(2.0));
+    /// assert!(!inspection.is_spam(5.0));
+    /// ```
+    pub fn total_score(&self) -> f64 {
+        self.spam_checks.iter().map(|check| check.score).sum()
+    }
+
+    /// Checks whether the total score reaches a threshold
+    ///
+    /// See [total_score].
+    ///
+    /// [total_score]: #method.total_score
+    pub fn is_spam(&self, threshold: f64) -> bool {
+        self.total_score() >= threshold
+    }
+}
+
+/// SpamCheck is a spam rule which matched a message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SpamCheck {
+    /// The code of the rule e.g. `HTML_MESSAGE`
+    pub code: String,
+    /// The score the rule adds
+    #[serde(default)]
+    pub score: f64,
+    /// A human readable description of the rule
+    #[serde(default)]
+    pub description: String,
+}
@@ -0,0 +1,29 @@
+/// Endpoints holds paths of Postal API methods
+///
+/// The paths are relative to the base URL of a client,
+/// so a base URL with a path like `https://host/postal/`
+/// is respected.
+/// A path starting with `/` replaces the path of the base URL.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Endpoints {
+    /// The path used to send a message
+    pub send_message: String,
+    /// The path used to send a raw message
+    pub send_raw: String,
+    /// The path used to get details of a message
+    pub message: String,
+    /// The path used to get deliveries of a message
+    pub deliveries: String,
+}
+
+impl Default for Endpoints {
+    /// Paths of the `v1` API
+    fn default() -> Self {
+        Self {
+            send_message: "api/v1/send/message".to_owned(),
+            send_raw: "api/v1/send/raw".to_owned(),
+            message: "api/v1/messages/message".to_owned(),
+            deliveries: "api/v1/messages/deliveries".to_owned(),
+        }
+    }
+}
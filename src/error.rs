@@ -1,14 +1,127 @@
+use crate::{RateLimitInfo, SendResult};
+use reqwest::StatusCode;
+use std::{fmt, time::Duration};
 use thiserror::Error;
 
 /// PostalError denotes crate erorrs
+///
+/// Errors of other crates are not included in the messages,
+/// they are available as a [source] so error reporters
+/// like `anyhow` print them as a chain.
+/// An i/o error is the exception, its message is the only thing
+/// which tells what went wrong e.g. that a file is missing.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::{Client, PostalError};
+/// use std::error::Error;
+///
+/// let err = Client::new("https://", "token").unwrap_err();
+///
+/// assert!(matches!(err, PostalError::UrlIssue(_)));
+/// assert_eq!(err.to_string(), "invalid URL of postal");
+/// assert_eq!(
+///     err.source().unwrap().to_string(),
+///     url::ParseError::EmptyHost.to_string()
+/// );
+/// ```
+///
+/// # Matching
+///
+/// New variants may be added in minor releases,
+/// so a match must have a wildcard arm.
+/// Predicates like [is_retriable] cover common groups of errors.
+///
+/// ```
+/// use postal_rs::PostalError;
+///
+/// fn describe(err: &PostalError) -> &'static str {
+///     match err {
+///         PostalError::MissingToken => "set POSTAL_TOKEN",
+///         err if err.is_auth_error() => "check the API key",
+///         err if err.is_retriable() => "try again later",
+///         _ => "failed to send",
+///     }
+/// }
+///
+/// assert_eq!(describe(&PostalError::MissingToken), "set POSTAL_TOKEN");
+/// assert_eq!(describe(&PostalError::ServiceUnavailableError), "try again later");
+/// assert_eq!(describe(&PostalError::ExpectedAlternativeUrl), "failed to send");
+/// ```
+///
+/// [source]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+/// [is_retriable]: #method.is_retriable
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum PostalError {
-    #[error("data store disconnected")]
+    #[error("failed to send a request to postal")]
     Network(#[from] reqwest::Error),
-    #[error("data store disconnected")]
+    #[error("invalid URL of postal")]
     UrlIssue(#[from] url::ParseError),
-    #[error("send error({code:?}): {message:?}")]
-    Error { code: String, message: String },
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unexpected response format")]
+    Json(#[from] serde_json::Error),
+    #[error("postal returned a body which is not JSON")]
+    Decode {
+        /// The body of the response
+        body: String,
+        source: serde_json::Error,
+    },
+    #[error("invalid base64 data")]
+    Base64(#[from] base64::DecodeError),
+    #[error("send error({:?}): {message:?}", code.as_str())]
+    Error {
+        code: PostalErrorCode,
+        message: String,
+        /// The original body of the response
+        raw: Option<String>,
+    },
+    #[error("an API token is missing or empty")]
+    MissingToken,
+    #[error("postal didn't return the {0:?} expansion")]
+    MissingExpansion(&'static str),
+    #[error("invalid message: {0}")]
+    InvalidMessage(String),
+    #[error("invalid address in `{field}`: {value:?}")]
+    InvalidAddress { field: &'static str, value: String },
+    #[error("only a part of messages was sent")]
+    PartiallySent {
+        /// Results of messages which were sent
+        sent: Vec<SendResult>,
+        /// An error which stopped sending
+        source: Box<PostalError>,
+    },
+    #[error("a message is too large ({size} > {limit} bytes)")]
+    MessageTooLarge { size: usize, limit: usize },
+    #[error("a response of postal is larger than {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+    #[error("all recipients are suppressed")]
+    AllRecipientsSuppressed(
+        /// The suppressed recipients
+        Vec<String>,
+    ),
+    #[error("expected {expected} recipient(s) in the response but got {got}")]
+    UnexpectedRecipientCount { expected: usize, got: usize },
+    #[error("a message didn't reach a final status in time")]
+    PollTimeout,
+    #[error("a request was cancelled")]
+    Cancelled,
+    #[error("a webhook signature is missing or invalid")]
+    InvalidSignature,
+    #[cfg(feature = "webhook")]
+    #[error("cryptographic error")]
+    Crypto(#[from] openssl::error::ErrorStack),
+    #[error("postal rejected the request because of a rate limit")]
+    RateLimited(
+        /// The rate limit reported by the response, see [PostalError::retry_after]
+        ///
+        /// [PostalError::retry_after]: #method.retry_after
+        RateLimitInfo,
+    ),
+    #[error("postal returned an unexpected status {0}")]
+    UnexpectedStatus(StatusCode),
     #[error("internal error on postal side")]
     InternalServerError,
     #[error("postal server unavailable")]
@@ -16,3 +129,315 @@ pub enum PostalError {
     #[error("Request should likely be sent to an another URL")]
     ExpectedAlternativeUrl,
 }
+
+impl PostalError {
+    /// Checks whether a request may succeed if it's sent again later
+    ///
+    /// It's true for server side failures, rate limits,
+    /// `502`/`504` statuses of a gateway, timeouts and connection errors.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::InternalServerError | Self::ServiceUnavailableError | Self::RateLimited(_) => {
+                true
+            }
+            Self::UnexpectedStatus(status) => {
+                matches!(
+                    *status,
+                    StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT
+                )
+            }
+            Self::Network(err) => err.is_timeout() || err.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// Returns how long to wait before sending a rejected request again
+    ///
+    /// It's set only for [PostalError::RateLimited]
+    /// when a response has a `Retry-After` header.
+    ///
+    /// [PostalError::RateLimited]: #variant.RateLimited
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited(info) => info.retry_after.map(Duration::from_secs),
+            _ => None,
+        }
+    }
+
+    /// Checks whether Postal rejected the API key
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Error {
+                code: PostalErrorCode::AccessDenied | PostalErrorCode::InvalidServerAPIKey,
+                ..
+            }
+        )
+    }
+
+    /// Checks whether a message was refused,
+    /// either by the crate before sending or by Postal
+    ///
+    /// Such a message won't be sent until it's changed.
+    pub fn is_invalid_message(&self) -> bool {
+        match self {
+            Self::InvalidMessage(_)
+            | Self::InvalidAddress { .. }
+            | Self::MessageTooLarge { .. }
+            | Self::AllRecipientsSuppressed(_) => true,
+            Self::Error { code, .. } => matches!(
+                code,
+                PostalErrorCode::ValidationError
+                    | PostalErrorCode::NoRecipients
+                    | PostalErrorCode::NoContent
+                    | PostalErrorCode::TooManyToAddresses
+                    | PostalErrorCode::TooManyCCAddresses
+                    | PostalErrorCode::TooManyBCCAddresses
+                    | PostalErrorCode::FromAddressMissing
+                    | PostalErrorCode::UnauthenticatedFromAddress
+                    | PostalErrorCode::AttachmentMissingName
+                    | PostalErrorCode::AttachmentMissingData
+            ),
+            _ => false,
+        }
+    }
+
+    /// Checks whether a requested message doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::Error {
+                code: PostalErrorCode::MessageNotFound,
+                ..
+            }
+        )
+    }
+}
+
+/// PostalErrorCode denotes a code of an error returned by Postal
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum PostalErrorCode {
+    /// An API key wasn't provided
+    AccessDenied,
+    /// An API key is not valid
+    InvalidServerAPIKey,
+    /// A mail server is suspended
+    ServerSuspended,
+    /// A message with the given id doesn't exist
+    MessageNotFound,
+    /// A message couldn't be validated
+    ValidationError,
+    /// A message has no recipients
+    NoRecipients,
+    /// A message has no body
+    NoContent,
+    /// A message has too many `to` recipients
+    TooManyToAddresses,
+    /// A message has too many `cc` recipients
+    TooManyCCAddresses,
+    /// A message has too many `bcc` recipients
+    TooManyBCCAddresses,
+    /// A message has no `from` address
+    FromAddressMissing,
+    /// A `from` address is not allowed to be used by the server
+    UnauthenticatedFromAddress,
+    /// An attachment has no name
+    AttachmentMissingName,
+    /// An attachment has no data
+    AttachmentMissingData,
+    /// Parameters of a request are not valid
+    ParameterError,
+    /// Any other code
+    Other(String),
+}
+
+impl PostalErrorCode {
+    /// Returns the code as it was sent by Postal
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::AccessDenied => "AccessDenied",
+            Self::InvalidServerAPIKey => "InvalidServerAPIKey",
+            Self::ServerSuspended => "ServerSuspended",
+            Self::MessageNotFound => "MessageNotFound",
+            Self::ValidationError => "ValidationError",
+            Self::NoRecipients => "NoRecipients",
+            Self::NoContent => "NoContent",
+            Self::TooManyToAddresses => "TooManyToAddresses",
+            Self::TooManyCCAddresses => "TooManyCCAddresses",
+            Self::TooManyBCCAddresses => "TooManyBCCAddresses",
+            Self::FromAddressMissing => "FromAddressMissing",
+            Self::UnauthenticatedFromAddress => "UnauthenticatedFromAddress",
+            Self::AttachmentMissingName => "AttachmentMissingName",
+            Self::AttachmentMissingData => "AttachmentMissingData",
+            Self::ParameterError => "ParameterError",
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl From<String> for PostalErrorCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "AccessDenied" => Self::AccessDenied,
+            "InvalidServerAPIKey" => Self::InvalidServerAPIKey,
+            "ServerSuspended" => Self::ServerSuspended,
+            "MessageNotFound" => Self::MessageNotFound,
+            "ValidationError" => Self::ValidationError,
+            "NoRecipients" => Self::NoRecipients,
+            "NoContent" => Self::NoContent,
+            "TooManyToAddresses" => Self::TooManyToAddresses,
+            "TooManyCCAddresses" => Self::TooManyCCAddresses,
+            "TooManyBCCAddresses" => Self::TooManyBCCAddresses,
+            "FromAddressMissing" => Self::FromAddressMissing,
+            "UnauthenticatedFromAddress" => Self::UnauthenticatedFromAddress,
+            "AttachmentMissingName" => Self::AttachmentMissingName,
+            "AttachmentMissingData" => Self::AttachmentMissingData,
+            "ParameterError" => Self::ParameterError,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for PostalErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// MessageValidationError denotes a problem found by [Message::validate]
+///
+/// [Message::validate]: ./struct.Message.html#method.validate
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum MessageValidationError {
+    #[error("too many recipients in `{field}` ({count} > {max})", max = crate::MAX_RECIPIENTS)]
+    TooManyRecipients { field: &'static str, count: usize },
+    #[error("no recipients were set")]
+    NoRecipients,
+    #[error("a `from` address is not set")]
+    MissingFrom,
+    #[error("no body was set")]
+    MissingBody,
+    #[error("`from` has several addresses but `sender` is not set")]
+    MissingSender,
+    #[error("invalid address in `{field}`: {value:?}")]
+    InvalidAddress { field: &'static str, value: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: &str) -> PostalError {
+        PostalError::Error {
+            code: code.to_owned().into(),
+            message: String::new(),
+            raw: None,
+        }
+    }
+
+    #[test]
+    fn retriable_errors() {
+        let retriable = vec![
+            PostalError::InternalServerError,
+            PostalError::ServiceUnavailableError,
+            PostalError::RateLimited(RateLimitInfo::default()),
+            PostalError::UnexpectedStatus(StatusCode::BAD_GATEWAY),
+            PostalError::UnexpectedStatus(StatusCode::GATEWAY_TIMEOUT),
+        ];
+        for err in retriable {
+            assert!(err.is_retriable(), "{:?}", err);
+            assert!(!err.is_auth_error(), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn not_retriable_errors() {
+        let errors = vec![
+            PostalError::UnexpectedStatus(StatusCode::NOT_FOUND),
+            PostalError::UnexpectedStatus(StatusCode::UNAUTHORIZED),
+            PostalError::UnexpectedStatus(StatusCode::FORBIDDEN),
+            PostalError::ExpectedAlternativeUrl,
+            PostalError::MissingToken,
+            PostalError::Cancelled,
+            PostalError::PollTimeout,
+            PostalError::InvalidSignature,
+            PostalError::InvalidMessage("no recipients were set".to_owned()),
+            PostalError::ResponseTooLarge { limit: 1 },
+            PostalError::MessageTooLarge { size: 2, limit: 1 },
+            PostalError::MissingExpansion("status"),
+            PostalError::UnexpectedRecipientCount {
+                expected: 1,
+                got: 0,
+            },
+            error("InvalidServerAPIKey"),
+            error("ValidationError"),
+            error("MessageNotFound"),
+        ];
+        for err in errors {
+            assert!(!err.is_retriable(), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn network_errors() {
+        let err: PostalError = reqwest::Client::new()
+            .post("not a url")
+            .build()
+            .unwrap_err()
+            .into();
+        assert!(!err.is_retriable());
+    }
+
+    #[tokio::test]
+    async fn connection_errors_are_retriable() {
+        // nothing listens on the port
+        let err: PostalError = reqwest::get("http://127.0.0.1:1/")
+            .await
+            .unwrap_err()
+            .into();
+        assert!(err.is_retriable());
+    }
+
+    #[test]
+    fn auth_errors() {
+        assert!(error("AccessDenied").is_auth_error());
+        assert!(error("InvalidServerAPIKey").is_auth_error());
+        assert!(!error("ServerSuspended").is_auth_error());
+        assert!(!PostalError::MissingToken.is_auth_error());
+        assert!(!PostalError::UnexpectedStatus(StatusCode::UNAUTHORIZED).is_auth_error());
+    }
+
+    #[test]
+    fn invalid_message_errors() {
+        assert!(PostalError::InvalidMessage(String::new()).is_invalid_message());
+        assert!(PostalError::AllRecipientsSuppressed(Vec::new()).is_invalid_message());
+        assert!(error("NoRecipients").is_invalid_message());
+        assert!(error("UnauthenticatedFromAddress").is_invalid_message());
+        assert!(!error("InvalidServerAPIKey").is_invalid_message());
+        assert!(!PostalError::RateLimited(RateLimitInfo::default()).is_invalid_message());
+    }
+
+    #[test]
+    fn not_found_errors() {
+        assert!(error("MessageNotFound").is_not_found());
+        assert!(!error("NoRecipients").is_not_found());
+        assert!(!PostalError::UnexpectedStatus(StatusCode::NOT_FOUND).is_not_found());
+    }
+
+    #[test]
+    fn retry_after() {
+        let info = RateLimitInfo {
+            retry_after: Some(30),
+            ..RateLimitInfo::default()
+        };
+        assert_eq!(
+            PostalError::RateLimited(info).retry_after(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            PostalError::RateLimited(RateLimitInfo::default()).retry_after(),
+            None
+        );
+        assert_eq!(PostalError::InternalServerError.retry_after(), None);
+    }
+}
@@ -0,0 +1,45 @@
+use serde_json::Value as Json;
+use url::form_urlencoded::Serializer;
+
+/// Encodes a JSON object as `application/x-www-form-urlencoded` fields
+///
+/// Nested values are named the way Rails parses them,
+/// `to[]=a&to[]=b` for arrays and `headers[X-Key]=value` for objects,
+/// objects in arrays become `attachments[][name]=a.txt`.
+/// `null` values are skipped.
+pub(crate) fn encode(json: &Json) -> String {
+    let mut form = Serializer::new(String::new());
+    if let Json::Object(map) = json {
+        for (key, value) in map {
+            field(&mut form, key, value);
+        }
+    }
+
+    form.finish()
+}
+
+fn field(form: &mut Serializer<'_, String>, key: &str, value: &Json) {
+    match value {
+        Json::Null => {}
+        Json::Bool(value) => {
+            form.append_pair(key, if *value { "true" } else { "false" });
+        }
+        Json::Number(value) => {
+            form.append_pair(key, &value.to_string());
+        }
+        Json::String(value) => {
+            form.append_pair(key, value);
+        }
+        Json::Array(values) => {
+            let key = format!("{}[]", key);
+            for value in values {
+                field(form, &key, value);
+            }
+        }
+        Json::Object(map) => {
+            for (name, value) in map {
+                field(form, &format!("{}[{}]", key, name), value);
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{Message, RawMessage, SendResult};
+use std::sync::Arc;
+
+/// SentMessage is a message passed to a hook set by [ClientBuilder::on_sent]
+///
+/// [ClientBuilder::on_sent]: ./struct.ClientBuilder.html#method.on_sent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SentMessage<'a> {
+    /// A message sent by [Client::send] or its variants
+    ///
+    /// [Client::send]: ./struct.Client.html#method.send
+    Message(&'a Message),
+    /// A message sent by [Client::send_raw] or its variants
+    ///
+    /// [Client::send_raw]: ./struct.Client.html#method.send_raw
+    Raw(&'a RawMessage),
+}
+
+pub(crate) type SentHook = Arc<dyn Fn(SentMessage<'_>, &[SendResult]) + Send + Sync>;
+
+pub(crate) type Suppression = Arc<dyn Fn(&str) -> bool + Send + Sync>;
@@ -5,7 +5,7 @@
 //! ```no_run
 //! use postal_rs::{Client, DetailsInterest, Message, SendResult};
 //! use std::env;
-//! 
+//!
 //! #[tokio::main]
 //! async fn main() {
 //!    let address = env::var("POSTAL_ADDRESS").unwrap_or_default();
@@ -28,230 +28,2629 @@
 //! [Postal]: https://postal.atech.media/
 //! [API]: https://github.com/postalhq/postal/wiki/Using-the-API
 
+mod address;
+mod api;
+mod attachment;
+mod builder;
+mod de;
+mod delivery;
+mod details;
+mod endpoints;
 mod error;
+mod form;
+mod hook;
+mod in_flight;
+mod message_builder;
+mod meta;
+mod mime;
+mod priority;
+mod profile;
+mod propagation;
+mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod token;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
-pub use error::PostalError;
+pub use api::PostalApi;
+pub use attachment::{Attachment, EncodedAttachment, MessageAttachment};
+pub use builder::ClientBuilder;
+pub use delivery::Delivery;
+pub use details::{
+    Inspection, MessageDetails, MessageInfo, MessageStatus, MessageStatusInfo, SpamCheck,
+};
+pub use endpoints::Endpoints;
+pub use error::{MessageValidationError, PostalError, PostalErrorCode};
+pub use hook::SentMessage;
+pub use message_builder::MessageBuilder;
+pub use meta::{RateLimitHeaders, RateLimitInfo, ResponseFlags, ResponseMeta};
+pub use priority::Priority;
+pub use profile::WireProfile;
+#[cfg(feature = "otel")]
+pub use propagation::OtelPropagator;
+pub use propagation::{traceparent, ContextPropagator, TRACEPARENT_HEADER, TRACESTATE_HEADER};
+pub use template::Template;
+pub use token::{TokenContext, TokenProvider};
 
+use futures::future::{self, Either, Future};
+use futures::stream::{self, Stream, StreamExt};
+use hook::{SentHook, Suppression};
+use in_flight::InFlight;
 use reqwest::StatusCode;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value as Json;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use url::Url;
 
 /// Client holds a session information
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// Clones of a client share the same connection pool.
+///
+/// The API token is never printed by the `Debug` implementation.
+#[derive(Clone)]
 pub struct Client {
     address: Url,
     token: String,
+    validate_addresses: bool,
+    batch_concurrency: usize,
+    key_header: String,
+    bearer_auth: bool,
+    endpoints: Endpoints,
+    wire_profile: WireProfile,
+    form_encoded: bool,
+    serialize_nulls: bool,
+    default_from: Option<String>,
+    default_reply_to: Option<String>,
+    default_tag: Option<String>,
+    limit: Option<Arc<Semaphore>>,
+    in_flight: Arc<InFlight>,
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    on_sent: Option<SentHook>,
+    context_propagator: Option<Arc<dyn ContextPropagator>>,
+    suppression: Option<Suppression>,
+    max_message_size: Option<usize>,
+    max_response_bytes: Option<usize>,
+    rate_limit_headers: RateLimitHeaders,
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+    require_sender: bool,
+    http: reqwest::Client,
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("address", &self.address)
+            .field("token", &Redacted)
+            .field("validate_addresses", &self.validate_addresses)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("key_header", &self.key_header)
+            .field("bearer_auth", &self.bearer_auth)
+            .field("endpoints", &self.endpoints)
+            .field("wire_profile", &self.wire_profile)
+            .field("form_encoded", &self.form_encoded)
+            .field("serialize_nulls", &self.serialize_nulls)
+            .field("default_from", &self.default_from)
+            .field("default_reply_to", &self.default_reply_to)
+            .field("default_tag", &self.default_tag)
+            .field("limit", &self.limit)
+            .field("in_flight", &self.in_flight.count())
+            .field("token_provider", &self.token_provider.as_ref().map(|_| ..))
+            .field("on_sent", &self.on_sent.as_ref().map(|_| ..))
+            .field(
+                "context_propagator",
+                &self.context_propagator.as_ref().map(|_| ..),
+            )
+            .field("suppression", &self.suppression.as_ref().map(|_| ..))
+            .field("max_message_size", &self.max_message_size)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("rate_limit_headers", &self.rate_limit_headers)
+            .field("rate_limit", &self.rate_limit())
+            .field("require_sender", &self.require_sender)
+            .field("http", &self.http)
+            .finish()
+    }
 }
 
+impl PartialEq for Client {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+            && self.token == other.token
+            && self.validate_addresses == other.validate_addresses
+            && self.batch_concurrency == other.batch_concurrency
+            && self.key_header == other.key_header
+            && self.bearer_auth == other.bearer_auth
+            && self.endpoints == other.endpoints
+            && self.wire_profile == other.wire_profile
+            && self.form_encoded == other.form_encoded
+            && self.serialize_nulls == other.serialize_nulls
+            && self.default_from == other.default_from
+            && self.default_reply_to == other.default_reply_to
+            && self.default_tag == other.default_tag
+            && self.max_message_size == other.max_message_size
+            && self.max_response_bytes == other.max_response_bytes
+            && self.rate_limit_headers == other.rate_limit_headers
+            && self.require_sender == other.require_sender
+            && match (&self.token_provider, &other.token_provider) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+            && match (&self.on_sent, &other.on_sent) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+            && match (&self.context_propagator, &other.context_propagator) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+            && match (&self.suppression, &other.suppression) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl Eq for Client {}
+
 impl Client {
     /// Constructs a new instance of client
+    ///
+    /// A path of the URL is kept as a prefix of API paths
+    /// e.g. `https://host/postal` sends messages to
+    /// `https://host/postal/api/v1/send/message`.
+    ///
+    /// A URL without a scheme is considered to be an `https` one.
+    ///
+    /// It fails with [PostalError::MissingToken] if the token is empty
+    /// or consists of whitespaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, PostalError};
+    ///
+    /// assert!(Client::new("https://postal.example.com", "token").is_ok());
+    ///
+    /// let client = Client::new("postal.example.com", "token").unwrap();
+    /// assert_eq!(client.base_url().as_str(), "https://postal.example.com/");
+    /// let client = Client::new("postal.example.com:8080/postal", "token").unwrap();
+    /// assert_eq!(client.base_url().as_str(), "https://postal.example.com:8080/postal/");
+    /// let client = Client::new("http://localhost:5000", "token").unwrap();
+    /// assert_eq!(client.base_url().as_str(), "http://localhost:5000/");
+    ///
+    /// assert!(matches!(
+    ///     Client::new("file:///etc/postal", "token"),
+    ///     Err(PostalError::UrlIssue(_))
+    /// ));
+    /// assert!(matches!(
+    ///     Client::new("https://postal.example.com", ""),
+    ///     Err(PostalError::MissingToken)
+    /// ));
+    /// assert!(matches!(
+    ///     Client::new("https://postal.example.com", " \n"),
+    ///     Err(PostalError::MissingToken)
+    /// ));
+    /// ```
+    ///
+    /// [PostalError::MissingToken]: ./enum.PostalError.html#variant.MissingToken
     pub fn new<U, S>(url: U, token: S) -> Result<Self, PostalError>
     where
         U: AsRef<str>,
         S: Into<String>,
     {
-        let url = Url::parse(url.as_ref())?;
+        Self::from_url(builder::parse_url(url.as_ref())?, token)
+    }
+
+    /// Constructs a client from an already parsed URL
+    ///
+    /// The URL is handled the same way as by [new],
+    /// it fails with [PostalError::UrlIssue] if the URL has no host.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Client, PostalError};
+    /// use url::Url;
+    ///
+    /// let url = Url::parse("https://postal.example.com/postal").unwrap();
+    /// let client = Client::from_url(url, "token").unwrap();
+    /// assert_eq!(client.base_url().as_str(), "https://postal.example.com/postal/");
+    ///
+    /// let url = Url::parse("mailto:postmaster@example.com").unwrap();
+    /// assert!(matches!(
+    ///     Client::from_url(url, "token"),
+    ///     Err(PostalError::UrlIssue(_))
+    /// ));
+    /// ```
+    ///
+    /// [new]: #method.new
+    /// [PostalError::UrlIssue]: ./enum.PostalError.html#variant.UrlIssue
+    pub fn from_url<S: Into<String>>(url: Url, token: S) -> Result<Self, PostalError> {
+        ClientBuilder::from_url(url, token).build()
+    }
+
+    /// Constructs a client with a token taken from
+    /// the [TOKEN_ENV] environment variable
+    ///
+    /// [TOKEN_ENV]: ./constant.TOKEN_ENV.html
+    pub fn from_env<U: AsRef<str>>(url: U) -> Result<Self, PostalError> {
+        let token = env::var(TOKEN_ENV).map_err(|_| PostalError::MissingToken)?;
+        Self::new(url, read_token(&token)?)
+    }
+
+    /// Constructs a client with a token read from a file
+    ///
+    /// Whitespaces around the token are ignored.
+    pub fn from_token_file<U, P>(url: U, path: P) -> Result<Self, PostalError>
+    where
+        U: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        let token = fs::read_to_string(path)?;
+        Self::new(url, read_token(&token)?)
+    }
+
+    /// Creates a builder to configure a client
+    pub fn builder<U, S>(url: U, token: S) -> ClientBuilder
+    where
+        U: AsRef<str>,
+        S: Into<String>,
+    {
+        ClientBuilder::new(url, token)
+    }
+
+    /// Creates a client which uses another API token
+    ///
+    /// The new client shares the connection pool and the configuration
+    /// with this one, except a token provider which is not kept.
+    /// It may be used to send on behalf of a few Postal servers,
+    /// see [TokenProvider] for a more flexible way.
+    ///
+    /// It fails with [PostalError::MissingToken] if the token is empty
+    /// or consists of whitespaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::{Message, PostalError};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let client = mock.client();
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///
+    ///     let first = client.with_token("first").unwrap();
+    ///     let second = client.with_token("second").unwrap();
+    ///     first.send_ref(&message).await.unwrap();
+    ///     second.send_ref(&message).await.unwrap();
+    ///
+    ///     let requests = mock.requests();
+    ///     assert_eq!(requests[0].header("X-Server-API-Key"), Some("first"));
+    ///     assert_eq!(requests[1].header("X-Server-API-Key"), Some("second"));
+    ///
+    ///     assert!(matches!(client.with_token(" "), Err(PostalError::MissingToken)));
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [TokenProvider]: ./trait.TokenProvider.html
+    /// [PostalError::MissingToken]: ./enum.PostalError.html#variant.MissingToken
+    pub fn with_token<S: Into<String>>(&self, token: S) -> Result<Client, PostalError> {
         let token = token.into();
+        if token.trim().is_empty() {
+            return Err(PostalError::MissingToken);
+        }
 
-        Ok(Self {
-            address: url,
+        Ok(Client {
             token,
+            token_provider: None,
+            ..self.clone()
+        })
+    }
+
+    /// Creates a client which sends requests to another Postal server
+    ///
+    /// The new client shares the connection pool and the configuration
+    /// with this one.
+    /// The URL is handled the same way as by [new].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Client;
+    ///
+    /// let client = Client::new("https://postal.example.com", "token").unwrap();
+    /// let eu = client.with_base_url("https://eu.example.com/postal").unwrap();
+    ///
+    /// assert_eq!(eu.base_url().as_str(), "https://eu.example.com/postal/");
+    /// assert_eq!(client.base_url().as_str(), "https://postal.example.com/");
+    /// ```
+    ///
+    /// [new]: #method.new
+    pub fn with_base_url<U: AsRef<str>>(&self, url: U) -> Result<Client, PostalError> {
+        Ok(Client {
+            address: builder::base_url(builder::parse_url(url.as_ref())?)?,
+            ..self.clone()
         })
     }
 
+    /// Returns the URL of a Postal server the client sends requests to
+    pub fn base_url(&self) -> &Url {
+        &self.address
+    }
+
+    /// Returns the last rate limit reported by response headers
+    ///
+    /// It's shared by clones of the client.
+    /// Each response is available in [ResponseMeta::rate_limit] as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use postal_rs::{Client, Message, RateLimitHeaders, RateLimitInfo};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     mock.respond_with_headers(
+    ///         "/api/v1/send/message",
+    ///         &[("X-Quota-Limit", "100"), ("X-Quota-Remaining", "42"), ("X-Quota-Reset", "60")],
+    ///     );
+    ///     let client = Client::builder(mock.url(), MOCK_TOKEN)
+    ///         .rate_limit_headers(RateLimitHeaders {
+    ///             limit: "X-Quota-Limit".to_owned(),
+    ///             remaining: "X-Quota-Remaining".to_owned(),
+    ///             reset: "X-Quota-Reset".to_owned(),
+    ///         })
+    ///         .build()
+    ///         .unwrap();
+    ///     assert_eq!(client.rate_limit(), None);
+    ///
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///     let (_, meta) = client.send_with_meta(message).await.unwrap();
+    ///
+    ///     let info = RateLimitInfo {
+    ///         limit: Some(100),
+    ///         remaining: Some(42),
+    ///         reset: Some(60),
+    ///         retry_after: None,
+    ///     };
+    ///     assert_eq!(meta.rate_limit, Some(info.clone()));
+    ///     assert_eq!(client.rate_limit(), Some(info));
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [ResponseMeta::rate_limit]: ./struct.ResponseMeta.html#structfield.rate_limit
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit.lock().unwrap().clone()
+    }
+
+    /// Checks whether the client has a non empty API token
+    /// or a token provider
+    pub fn has_token(&self) -> bool {
+        self.token_provider.is_some() || !self.token.is_empty()
+    }
+
+    /// Returns how many requests the client and all its clones
+    /// are sending at the moment
+    pub fn in_flight_requests(&self) -> usize {
+        self.in_flight.count()
+    }
+
+    /// Waits until requests sent by the client and all its clones complete
+    ///
+    /// It resolves immediately if there's no requests being sent.
+    /// It may be used on a shutdown of an application
+    /// so messages are not dropped in the middle of sending.
+    ///
+    /// The client and its clones should not be used after the shutdown,
+    /// requests sent by them are not waited for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::Message;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let client = mock.client();
+    ///     for i in 0..3 {
+    ///         let client = client.clone();
+    ///         tokio::spawn(async move {
+    ///             let message = Message::default()
+    ///                 .to(&[format!("user{}@gmail.com", i)])
+    ///                 .from("test@yourserver.io")
+    ///                 .text("A test message");
+    ///             client.send(message).await
+    ///         });
+    ///     }
+    ///
+    ///     while mock.requests().len() < 3 {
+    ///         tokio::task::yield_now().await;
+    ///     }
+    ///
+    ///     let monitor = client.clone();
+    ///     client.shutdown().await;
+    ///
+    ///     assert_eq!(monitor.in_flight_requests(), 0);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub async fn shutdown(self) {
+        self.in_flight.wait().await
+    }
+
     /// Sends a message to Postal
+    ///
+    /// The message isn't checked before sending,
+    /// problems like missing recipients are reported by Postal.
+    /// Use [send_dry_run] to check a message without sending it.
+    ///
+    /// [send_dry_run]: #method.send_dry_run
     pub async fn send<M: Into<Message>>(&self, message: M) -> Result<Vec<SendResult>, PostalError> {
-        let address = self.address.join("/api/v1/send/message")?;
+        self.send_ref(&message.into()).await
+    }
+
+    /// Sends a message to Postal unless it's cancelled
+    ///
+    /// It fails with [PostalError::Cancelled] if `cancel` completes first,
+    /// the request is aborted then.
+    /// Any future can be used as a signal, e.g. a `oneshot` receiver
+    /// or `CancellationToken::cancelled` from `tokio-util`.
+    ///
+    /// Dropping a future returned by any method of the client
+    /// aborts its request as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::{Message, PostalError};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///
+    ///     let (cancel, cancelled) = tokio::sync::oneshot::channel::<()>();
+    ///     cancel.send(()).unwrap();
+    ///
+    ///     let result = mock.client().send_cancellable(message, cancelled).await;
+    ///
+    ///     assert!(matches!(result, Err(PostalError::Cancelled)));
+    ///     assert!(mock.requests().is_empty());
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [PostalError::Cancelled]: ./enum.PostalError.html#variant.Cancelled
+    pub async fn send_cancellable<M, C>(
+        &self,
+        message: M,
+        cancel: C,
+    ) -> Result<Vec<SendResult>, PostalError>
+    where
+        M: Into<Message>,
+        C: Future,
+    {
+        let message = message.into();
+        let send = self.send_ref(&message);
+        futures::pin_mut!(send, cancel);
+
+        // the signal is polled first so a cancelled send is never started
+        match future::select(cancel, send).await {
+            Either::Left(_) => Err(PostalError::Cancelled),
+            Either::Right((result, _)) => result,
+        }
+    }
+
+    /// Sends a message with a single recipient to Postal
+    ///
+    /// It returns [PostalError::UnexpectedRecipientCount]
+    /// if Postal returns not exactly one result.
+    ///
+    /// [PostalError::UnexpectedRecipientCount]: ./enum.PostalError.html#variant.UnexpectedRecipientCount
+    pub async fn send_one<M: Into<Message>>(&self, message: M) -> Result<SendResult, PostalError> {
+        let mut results = self.send(message).await?;
+        if results.len() != 1 {
+            return Err(PostalError::UnexpectedRecipientCount {
+                expected: 1,
+                got: results.len(),
+            });
+        }
+
+        Ok(results.remove(0))
+    }
+
+    /// Builds a message and sends it to Postal
+    ///
+    /// See [MessageBuilder::build].
+    ///
+    /// [MessageBuilder::build]: ./struct.MessageBuilder.html#method.build
+    pub async fn send_builder(
+        &self,
+        builder: MessageBuilder,
+    ) -> Result<Vec<SendResult>, PostalError> {
+        self.send_ref(&builder.build()?).await
+    }
+
+    /// Sends a message to Postal without taking ownership of it
+    ///
+    /// It allows the same message to be sent several times without cloning it.
+    /// A message is cloned only if the client normalizes addresses
+    /// or fills in its default `from`, `reply_to` or `tag`.
+    pub async fn send_ref(&self, message: &Message) -> Result<Vec<SendResult>, PostalError> {
+        let (results, _) = self.send_ref_with_meta(message).await?;
+        Ok(results)
+    }
+
+    /// Sends a message to Postal and returns the results
+    /// together with metadata of the response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::Message;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///     let body = message.to_json().unwrap();
+    ///
+    ///     let (_, meta) = mock.client().send_with_meta(message).await.unwrap();
+    ///
+    ///     assert_eq!(meta.bytes_sent, body.len());
+    ///     assert_eq!(mock.requests()[0].body, body);
+    ///     assert!(meta.bytes_received > 0);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub async fn send_with_meta<M: Into<Message>>(
+        &self,
+        message: M,
+    ) -> Result<(Vec<SendResult>, ResponseMeta), PostalError> {
+        self.send_ref_with_meta(&message.into()).await
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, message),
+            fields(endpoint = "send/message", recipients = tracing::field::Empty)
+        )
+    )]
+    async fn send_ref_with_meta(
+        &self,
+        message: &Message,
+    ) -> Result<(Vec<SendResult>, ResponseMeta), PostalError> {
+        let message = self.prepare(message)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("recipients", message.recipient_count());
+
+        let path = &self.endpoints.send_message;
+        let from = message.from.as_deref();
+        let profile = self.wire_profile;
+        let (data, meta) = if self.form_encoded {
+            let body = form::encode(&profile.serialize(&message)?);
+            self.request_body_with_meta(path, body.into_bytes(), FORM_CONTENT_TYPE, from)
+                .await?
+        } else if profile == WireProfile::Postal && !self.serialize_nulls {
+            self.request_with_meta(path, &*message, from).await?
+        } else {
+            let mut body = profile.serialize(&message)?;
+            if self.serialize_nulls {
+                profile.fill_nulls(&mut body);
+            }
+            self.request_with_meta(path, &body, from).await?
+        };
+
+        let results = send_results(data);
+        if let Some(hook) = &self.on_sent {
+            hook(SentMessage::Message(&message), &results);
+        }
+
+        Ok((results, meta))
+    }
+
+    /// Sends independent messages
+    ///
+    /// The messages are sent concurrently,
+    /// at most [batch_concurrency] at a time.
+    /// The results are returned in the order of `messages`.
+    ///
+    /// [batch_concurrency]: ./struct.ClientBuilder.html#method.batch_concurrency
+    pub async fn send_batch(
+        &self,
+        messages: Vec<Message>,
+    ) -> Vec<Result<Vec<SendResult>, PostalError>> {
+        stream::iter(messages)
+            .map(|message| async move { self.send_ref(&message).await })
+            .buffered(self.batch_concurrency)
+            .collect()
+            .await
+    }
+
+    /// Sends a message to a long list of `to` recipients
+    /// by splitting it into several messages
+    ///
+    /// Each message gets at most `chunk_size` recipients,
+    /// which is capped by [MAX_RECIPIENTS].
+    /// `cc` and `bcc` recipients are kept only in the first message
+    /// so they get a single copy.
+    ///
+    /// If a message fails to be sent the remaining ones are not sent
+    /// and [PostalError::PartiallySent] is returned with results of the ones
+    /// which were sent.
+    ///
+    /// [MAX_RECIPIENTS]: ./constant.MAX_RECIPIENTS.html
+    /// [PostalError::PartiallySent]: ./enum.PostalError.html#variant.PartiallySent
+    pub async fn send_chunked(
+        &self,
+        mut message: Message,
+        chunk_size: usize,
+    ) -> Result<Vec<SendResult>, PostalError> {
+        let chunk_size = chunk_size.clamp(1, MAX_RECIPIENTS);
+        let to = message.to.take().unwrap_or_default();
+        if to.is_empty() {
+            return self.send(message).await;
+        }
+
+        let mut results = Vec::with_capacity(to.len());
+        for (i, chunk) in to.chunks(chunk_size).enumerate() {
+            let mut message = message.clone().to(chunk);
+            if i > 0 {
+                message.cc = None;
+                message.bcc = None;
+            }
+
+            match self.send(message).await {
+                Ok(sent) => results.extend(sent),
+                Err(err) => {
+                    return Err(PostalError::PartiallySent {
+                        sent: results,
+                        source: Box::new(err),
+                    })
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Sends a message to a stream of `to` recipients
+    ///
+    /// The recipients are collected into messages of at most `chunk_size`
+    /// recipients, which is capped by [MAX_RECIPIENTS].
+    /// The messages are sent concurrently, at most [batch_concurrency] at a time,
+    /// and results of each message are yielded as soon as it's sent.
+    /// Recipients are not taken from the stream
+    /// until the results are consumed, which gives a backpressure.
+    ///
+    /// Unlike [send_chunked] it doesn't stop on an error.
+    /// `cc` and `bcc` recipients are kept only in the first message
+    /// so they get a single copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::Message;
+    /// use futures::stream::{self, StreamExt};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let client = mock.client();
+    ///     let message = Message::default()
+    ///         .from("test@yourserver.io")
+    ///         .subject("Newsletter")
+    ///         .text("A test message");
+    ///     let recipients = stream::iter((0..120).map(|i| format!("user{}@gmail.com", i)));
+    ///
+    ///     let mut sizes: Vec<usize> = client
+    ///         .send_mailing(message, recipients, 50)
+    ///         .map(|results| results.unwrap().len())
+    ///         .collect()
+    ///         .await;
+    ///     sizes.sort();
+    ///
+    ///     assert_eq!(sizes, [20, 50, 50]);
+    ///     assert_eq!(mock.requests().len(), 3);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [MAX_RECIPIENTS]: ./constant.MAX_RECIPIENTS.html
+    /// [batch_concurrency]: ./struct.ClientBuilder.html#method.batch_concurrency
+    /// [send_chunked]: #method.send_chunked
+    pub fn send_mailing<'a, S>(
+        &'a self,
+        base: Message,
+        recipients: S,
+        chunk_size: usize,
+    ) -> impl Stream<Item = Result<Vec<SendResult>, PostalError>> + 'a
+    where
+        S: Stream<Item = String> + 'a,
+    {
+        let chunk_size = chunk_size.clamp(1, MAX_RECIPIENTS);
+        recipients
+            .chunks(chunk_size)
+            .enumerate()
+            .map(move |(i, chunk)| {
+                let mut message = base.clone().to(&chunk);
+                if i > 0 {
+                    message.cc = None;
+                    message.bcc = None;
+                }
+
+                async move { self.send_ref(&message).await }
+            })
+            .buffer_unordered(self.batch_concurrency)
+    }
+
+    /// Sends a message to Postal and checks that
+    /// each of its recipients was accepted
+    ///
+    /// Recipients from `to`, `cc` and `bcc` which didn't get
+    /// a message id are returned in [SendOutcome::missing].
+    ///
+    /// [SendOutcome::missing]: ./struct.SendOutcome.html#structfield.missing
+    pub async fn send_with_outcome<M: Into<Message>>(
+        &self,
+        message: M,
+    ) -> Result<SendOutcome, PostalError> {
+        let message = message.into();
+        let recipients: Vec<String> = message.recipients().map(str::to_owned).collect();
+
+        let accepted = self.send(message).await?;
+        let missing = recipients
+            .into_iter()
+            .filter(|recipient| {
+                let recipient = address::mailbox(recipient);
+                !accepted
+                    .iter()
+                    .any(|result| result.to.eq_ignore_ascii_case(recipient))
+            })
+            .collect();
+
+        Ok(SendOutcome { accepted, missing })
+    }
+
+    /// Sends a message tagged with [TEST_TAG]
+    ///
+    /// It's meant for integration tests against a real Postal server.
+    /// The send API of Postal has no option to accept a message but hold it,
+    /// holding is configured on the Postal side instead.
+    /// Either use a credential which is set to hold messages
+    /// or route the [TEST_TAG] tag so messages don't reach real inboxes.
+    /// A tag of the message is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::{Message, TEST_TAG};
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///
+    ///     let results = mock.client().send_test(message).await.unwrap();
+    ///
+    ///     assert_eq!(results.len(), 1);
+    ///     assert_eq!(mock.requests()[0].json().unwrap()["tag"], TEST_TAG);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [TEST_TAG]: ./constant.TEST_TAG.html
+    pub async fn send_test<M: Into<Message>>(
+        &self,
+        message: M,
+    ) -> Result<Vec<SendResult>, PostalError> {
+        let mut message = message.into();
+        message.tag = Some(TEST_TAG.to_owned());
+        self.send_ref(&message).await
+    }
+
+    /// Prepares a message for sending without contacting Postal
+    ///
+    /// It prepares the message the way [send] does, checks it
+    /// and returns the message which would be sent.
+    /// [send] leaves the checks to Postal.
+    /// It fails if there's no recipients, if there's more then [MAX_RECIPIENTS]
+    /// in any of `to`, `cc`, `bcc` or if `from` is not set.
+    ///
+    /// If the client was built with address validation
+    /// the addresses are normalized as well.
+    /// Defaults of the client are filled in, see [ClientBuilder::default_from].
+    ///
+    /// [send]: #method.send
+    /// [MAX_RECIPIENTS]: ./constant.MAX_RECIPIENTS.html
+    /// [ClientBuilder::default_from]: ./struct.ClientBuilder.html#method.default_from
+    pub fn send_dry_run<M: Into<Message>>(&self, message: M) -> Result<Message, PostalError> {
+        let message = message.into();
+        let prepared = self.prepare(&message)?;
+        prepared.check()?;
+
+        Ok(match prepared {
+            Cow::Borrowed(_) => message,
+            Cow::Owned(prepared) => prepared,
+        })
+    }
+
+    /// Fills in defaults of the client and normalizes addresses if it's turned on
+    fn prepare<'a>(&self, message: &'a Message) -> Result<Cow<'a, Message>, PostalError> {
+        let mut message = Cow::Borrowed(message);
+        if message.from.is_none() && self.default_from.is_some() {
+            message.to_mut().from = self.default_from.clone();
+        }
+        if message.reply_to.is_none() && self.default_reply_to.is_some() {
+            message.to_mut().reply_to = self.default_reply_to.clone();
+        }
+        if message.tag.is_none() && self.default_tag.is_some() {
+            message.to_mut().tag = self.default_tag.clone();
+        }
+
+        if let Some(suppression) = &self.suppression {
+            let is_suppressed = |to: &str| suppression(to);
+            if message
+                .recipients()
+                .any(|to| is_suppressed(address::mailbox(to)))
+            {
+                let message = message.to_mut();
+                let suppressed = message.remove_suppressed(is_suppressed);
+                if message.recipient_count() == 0 {
+                    return Err(PostalError::AllRecipientsSuppressed(suppressed));
+                }
+            }
+        }
+
+        if self.validate_addresses {
+            message = Cow::Owned(message.into_owned().validate_addresses()?);
+        }
+
+        if self.require_sender && message.needs_sender() {
+            return Err(PostalError::InvalidMessage(
+                "`from` has several addresses but `sender` is not set".to_owned(),
+            ));
+        }
+
+        #[cfg(feature = "tracing")]
+        if message.needs_sender() {
+            tracing::warn!("`from` has several addresses but `sender` is not set");
+        }
+
+        if let Some(limit) = self.max_message_size {
+            let size = message.estimated_size();
+            if size > limit {
+                return Err(PostalError::MessageTooLarge { size, limit });
+            }
+        }
+
+        Ok(message)
+    }
+
+    /// Sends a standart SMTP message to Postal
+    pub async fn send_raw<M: Into<RawMessage>>(
+        &self,
+        message: M,
+    ) -> Result<Vec<SendResult>, PostalError> {
+        let (results, _) = self.send_raw_with_meta(message).await?;
+        Ok(results)
+    }
+
+    /// Sends a standart SMTP message to Postal and returns the results
+    /// together with metadata of the response
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, message),
+            fields(endpoint = "send/raw", recipients = tracing::field::Empty)
+        )
+    )]
+    pub async fn send_raw_with_meta<M: Into<RawMessage>>(
+        &self,
+        message: M,
+    ) -> Result<(Vec<SendResult>, ResponseMeta), PostalError> {
         let message = message.into();
 
-        let client = reqwest::Client::new();
-        let res = client
-            .post(address)
-            .json(&message)
-            .header("X-Server-API-Key", &self.token)
-            .send()
-            .await?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("recipients", message.rcpt_to.len());
+
+        let (data, meta) = self
+            .request_with_meta(&self.endpoints.send_raw, &message, Some(&message.mail_from))
+            .await?;
+
+        let results = send_results(data);
+        if let Some(hook) = &self.on_sent {
+            hook(SentMessage::Raw(&message), &results);
+        }
+
+        Ok((results, meta))
+    }
+
+    /// Asks a Postal server to provide an information details
+    /// about a message
+    ///
+    /// By default it provides a limited information.
+    /// To increase this volume you can specify expansions via [DetailsInterest]
+    ///
+    /// [DetailsInterest]: ./struct.DetailsInterest.html
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, interest), fields(endpoint = "messages/message"))
+    )]
+    pub async fn get_message_details<I: Into<DetailsInterest>>(
+        &self,
+        interest: I,
+    ) -> Result<HashMap<String, Json>, PostalError> {
+        self.fetch_message_details(interest.into()).await
+    }
+
+    /// Waits until a message reaches a final status
+    ///
+    /// The status is requested every `poll_interval`
+    /// until it's final (see [MessageStatus::is_final]).
+    /// It fails with [PostalError::PollTimeout] if it's not final
+    /// within `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::MessageStatus;
+    /// use std::time::Duration;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     for status in &["Pending", "Sent"] {
+    ///         mock.respond_success(
+    ///             "/api/v1/messages/message",
+    ///             serde_json::json!({ "id": 1, "token": "abc", "status": { "status": status } }),
+    ///         );
+    ///     }
+    ///
+    ///     let status = mock
+    ///         .client()
+    ///         .await_final_status(1, Duration::from_millis(10), Duration::from_secs(5))
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     assert_eq!(status, MessageStatus::Sent);
+    ///     assert_eq!(mock.requests().len(), 2);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [MessageStatus::is_final]: ./enum.MessageStatus.html#method.is_final
+    /// [PostalError::PollTimeout]: ./enum.PostalError.html#variant.PollTimeout
+    pub async fn await_final_status(
+        &self,
+        id: MessageHash,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<MessageStatus, PostalError> {
+        let poll = async {
+            loop {
+                let interest = DetailsInterest::new(id).with_status();
+                let details: MessageDetails = self.fetch_message_details(interest).await?;
+                let status = details
+                    .status
+                    .ok_or(PostalError::MissingExpansion("status"))?
+                    .status;
+                if status.is_final() {
+                    return Ok(status);
+                }
+
+                tokio::time::delay_for(poll_interval).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| PostalError::PollTimeout)?
+    }
+
+    /// Sends a message and obtains details about
+    /// each of the sent messages.
+    ///
+    /// The `interest` function is called with an id of each message
+    /// to choose which expansions should be requested.
+    pub async fn send_and_inspect<M, F>(
+        &self,
+        message: M,
+        interest: F,
+    ) -> Result<Vec<(SendResult, MessageDetails)>, PostalError>
+    where
+        M: Into<Message>,
+        F: Fn(MessageHash) -> DetailsInterest,
+    {
+        let results = self.send(message).await?;
+
+        let mut reports = Vec::with_capacity(results.len());
+        for result in results {
+            let details = self.fetch_message_details(interest(result.id)).await?;
+            reports.push((result, details));
+        }
+
+        Ok(reports)
+    }
+
+    /// Sends a message once again
+    ///
+    /// Postal's API can't retry a delivery,
+    /// so the raw message is fetched and sent as a new one
+    /// to the same recipient via [send_raw].
+    ///
+    /// [send_raw]: #method.send_raw
+    pub async fn resend_from_details(
+        &self,
+        id: MessageHash,
+    ) -> Result<Vec<SendResult>, PostalError> {
+        let interest = DetailsInterest::new(id).with_details().with_raw_message();
+        let details: MessageDetails = self.fetch_message_details(interest).await?;
+
+        let info = details
+            .details
+            .ok_or(PostalError::MissingExpansion("details"))?;
+        let rcpt_to = info
+            .rcpt_to
+            .ok_or(PostalError::MissingExpansion("details"))?;
+        let mail_from = info
+            .mail_from
+            .ok_or(PostalError::MissingExpansion("details"))?;
+        let data = details
+            .raw_message
+            .ok_or(PostalError::MissingExpansion("raw_message"))?;
+
+        self.send_raw(RawMessage::new(&[rcpt_to], mail_from, data))
+            .await
+    }
+
+    /// Obtains an RFC2822 source of a message
+    ///
+    /// It requests the `raw_message` expansion and decodes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     mock.respond_success(
+    ///         "/api/v1/messages/message",
+    ///         serde_json::json!({
+    ///             "id": 1,
+    ///             "token": "abc",
+    ///             "raw_message": base64::encode("Subject: Hi\r\n\r\nHello"),
+    ///         }),
+    ///     );
+    ///
+    ///     let raw = mock.client().get_raw_message(1).await.unwrap();
+    ///
+    ///     assert_eq!(raw, b"Subject: Hi\r\n\r\nHello");
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub async fn get_raw_message(&self, id: MessageHash) -> Result<Vec<u8>, PostalError> {
+        let interest = DetailsInterest::new(id).with_raw_message();
+        let details: MessageDetails = self.fetch_message_details(interest).await?;
+        let data = details
+            .raw_message
+            .ok_or(PostalError::MissingExpansion("raw_message"))?;
+
+        let data: String = data.split_whitespace().collect();
+        Ok(base64::decode(data)?)
+    }
+
+    /// Obtains attachments of a message
+    ///
+    /// It requests the `attachments` expansion and decodes its content.
+    /// A message without attachments gets an empty list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     mock.respond_success(
+    ///         "/api/v1/messages/message",
+    ///         serde_json::json!({
+    ///             "id": 1,
+    ///             "token": "abc",
+    ///             "attachments": [
+    ///                 {
+    ///                     "filename": "hello.txt",
+    ///                     "content_type": "text/plain",
+    ///                     "data": base64::encode("Hello"),
+    ///                     "size": 5,
+    ///                     "hash": "f7ff9e8b7bb2e09b70935a5d785e0cc5d9d0abf0"
+    ///                 },
+    ///                 {
+    ///                     "filename": "data.bin",
+    ///                     "content_type": "application/octet-stream",
+    ///                     "data": base64::encode([0, 159, 255]),
+    ///                     "size": 3,
+    ///                     "hash": "a1c3a4c1ab2a1c4f2f1aee0a4fe0b2a4e5e0e1d2"
+    ///                 }
+    ///             ],
+    ///         }),
+    ///     );
+    ///     mock.respond_success(
+    ///         "/api/v1/messages/message",
+    ///         serde_json::json!({ "id": 2, "token": "def", "attachments": [] }),
+    ///     );
+    ///     let client = mock.client();
+    ///
+    ///     let attachments = client.get_attachments(1).await.unwrap();
+    ///     assert_eq!(attachments.len(), 2);
+    ///     assert_eq!(attachments[0].name, "hello.txt");
+    ///     assert_eq!(attachments[0].content_type, "text/plain");
+    ///     assert_eq!(attachments[0].data, b"Hello");
+    ///     assert_eq!(attachments[1].name, "data.bin");
+    ///     assert_eq!(attachments[1].data, [0, 159, 255]);
+    ///     assert_eq!(
+    ///         mock.requests()[0].json().unwrap()["_expansions"],
+    ///         serde_json::json!(["attachments"])
+    ///     );
+    ///
+    ///     assert!(client.get_attachments(2).await.unwrap().is_empty());
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    pub async fn get_attachments(&self, id: MessageHash) -> Result<Vec<Attachment>, PostalError> {
+        let interest = DetailsInterest::new(id).with_attachments();
+        let details: MessageDetails = self.fetch_message_details(interest).await?;
+
+        details
+            .attachments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|attachment| Ok(serde_json::from_value(attachment)?))
+            .collect()
+    }
+
+    async fn fetch_message_details<T: DeserializeOwned>(
+        &self,
+        interest: DetailsInterest,
+    ) -> Result<T, PostalError> {
+        let body: Json = interest.into();
+        self.request(&self.endpoints.message, &body, None).await
+    }
+
+    /// Obtains a delivery information according to a message.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(endpoint = "messages/deliveries"))
+    )]
+    pub async fn get_message_deliveries(
+        &self,
+        id: MessageHash,
+    ) -> Result<Vec<HashMap<String, Json>>, PostalError> {
+        self.fetch_message_deliveries(id).await
+    }
+
+    /// Obtains deliveries of a list of messages.
+    ///
+    /// The requests are sent concurrently,
+    /// at most [batch_concurrency] at a time.
+    /// The results are returned in the order of `ids`.
+    ///
+    /// [batch_concurrency]: ./struct.ClientBuilder.html#method.batch_concurrency
+    pub async fn get_deliveries_batch(
+        &self,
+        ids: &[MessageHash],
+    ) -> Vec<Result<(MessageHash, Vec<Delivery>), PostalError>> {
+        stream::iter(ids.iter().copied())
+            .map(|id| async move {
+                let deliveries = self.fetch_message_deliveries(id).await?;
+                Ok((id, deliveries))
+            })
+            .buffered(self.batch_concurrency)
+            .collect()
+            .await
+    }
+
+    /// Obtains details and deliveries of a message at once
+    ///
+    /// Both requests are sent concurrently.
+    pub async fn get_message_report<I: Into<DetailsInterest>>(
+        &self,
+        interest: I,
+    ) -> Result<MessageReport, PostalError> {
+        let interest = interest.into();
+        let id = interest.id;
+        let (details, deliveries) = futures::try_join!(
+            self.fetch_message_details(interest),
+            self.fetch_message_deliveries(id),
+        )?;
+
+        Ok(MessageReport {
+            details,
+            deliveries,
+        })
+    }
+
+    async fn fetch_message_deliveries<T: DeserializeOwned>(
+        &self,
+        id: MessageHash,
+    ) -> Result<T, PostalError> {
+        let body: Json = serde_json::json!({ "id": id });
+        self.request(&self.endpoints.deliveries, &body, None).await
+    }
+
+    /// Sends a request to any API method of Postal
+    ///
+    /// The body is posted to `path`, which is joined
+    /// with the base URL the same way [Endpoints] are.
+    /// It returns the `data` of a successful response.
+    /// It may be used for API methods the crate doesn't cover.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::MockPostal;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     mock.respond_success("/api/v1/suppressions/list", serde_json::json!(["a@b.io"]));
+    ///
+    ///     let data = mock
+    ///         .client()
+    ///         .request_raw("api/v1/suppressions/list", serde_json::json!({ "page": 1 }))
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     assert_eq!(data, serde_json::json!(["a@b.io"]));
+    ///     assert_eq!(mock.requests()[0].json().unwrap()["page"], 1);
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [Endpoints]: ./struct.Endpoints.html
+    pub async fn request_raw(&self, path: &str, body: Json) -> Result<Json, PostalError> {
+        self.request(path, &body, None).await
+    }
+
+    /// Creates a POST request to a path of Postal
+    ///
+    /// The path is joined with the base URL the same way [Endpoints] are.
+    /// The request has the API key and the trace context headers set,
+    /// anything else e.g. a body or a timeout is up to the caller.
+    /// The caller sends the request and parses the response itself,
+    /// a limit of [max_concurrent_requests] doesn't apply to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "testing")]
+    /// use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+    /// use std::time::Duration;
+    ///
+    /// # #[cfg(feature = "testing")]
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///
+    ///     let response = mock
+    ///         .client()
+    ///         .request_builder("api/v1/messages/deliveries")
+    ///         .unwrap()
+    ///         .header("X-Request-Id", "42")
+    ///         .timeout(Duration::from_secs(5))
+    ///         .json(&serde_json::json!({ "id": 1 }))
+    ///         .send()
+    ///         .await
+    ///         .unwrap();
+    ///     assert!(response.status().is_success());
+    ///
+    ///     let request = &mock.requests()[0];
+    ///     assert_eq!(request.header("X-Server-API-Key"), Some(MOCK_TOKEN));
+    ///     assert_eq!(request.header("X-Request-Id"), Some("42"));
+    /// }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// [Endpoints]: ./struct.Endpoints.html
+    /// [max_concurrent_requests]: ./struct.ClientBuilder.html#method.max_concurrent_requests
+    pub fn request_builder(&self, path: &str) -> Result<reqwest::RequestBuilder, PostalError> {
+        self.post(path, None)
+    }
+
+    fn post(&self, path: &str, from: Option<&str>) -> Result<reqwest::RequestBuilder, PostalError> {
+        let address = self.address.join(path)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(url = %address, "sending a request to postal");
+
+        let token = match &self.token_provider {
+            Some(provider) => provider.token_for(&TokenContext { path, from }),
+            None => self.token.clone(),
+        };
+
+        let mut req = self
+            .http
+            .post(address)
+            .header(self.key_header.as_str(), &token);
+        if self.bearer_auth {
+            req = req.bearer_auth(&token);
+        }
+        if let Some(propagator) = &self.context_propagator {
+            for (name, value) in propagator.headers() {
+                req = req.header(name.as_str(), value.as_str());
+            }
+        }
+
+        Ok(req)
+    }
+
+    async fn request<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+        from: Option<&str>,
+    ) -> Result<T, PostalError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let (data, _) = self.request_with_meta(path, body, from).await?;
+        Ok(data)
+    }
+
+    async fn request_with_meta<B, T>(
+        &self,
+        path: &str,
+        body: &B,
+        from: Option<&str>,
+    ) -> Result<(T, ResponseMeta), PostalError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let body = serde_json::to_vec(body)?;
+        self.request_body_with_meta(path, body, JSON_CONTENT_TYPE, from)
+            .await
+    }
+
+    async fn request_body_with_meta<T>(
+        &self,
+        path: &str,
+        body: Vec<u8>,
+        content_type: &str,
+        from: Option<&str>,
+    ) -> Result<(T, ResponseMeta), PostalError>
+    where
+        T: DeserializeOwned,
+    {
+        let req = self.post(path, from)?;
+
+        let _in_flight = self.in_flight.start();
+
+        let _permit = match &self.limit {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        };
+
+        let bytes_sent = body.len();
+
+        let res = req
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await?;
+
+        let rate_limit = self.rate_limit_headers.parse(res.headers());
+        if rate_limit.is_some() {
+            *self.rate_limit.lock().unwrap() = rate_limit.clone();
+        }
+
+        let (data, meta) = read_responce(res, self.max_response_bytes, rate_limit.as_ref()).await?;
+
+        Ok((
+            data,
+            ResponseMeta {
+                bytes_sent,
+                rate_limit,
+                ..meta
+            },
+        ))
+    }
+}
+
+fn read_token(token: &str) -> Result<String, PostalError> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(PostalError::MissingToken);
+    }
+
+    Ok(token.to_owned())
+}
+
+/// Redacted stands in for a secret in `Debug` output
+struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+fn send_results(data: api_structures::MessageSucessData) -> Vec<SendResult> {
+    data.messages
+        .into_iter()
+        .map(|(to, m)| SendResult {
+            to,
+            id: m.id,
+            token: m.token,
+        })
+        .collect()
+}
+
+fn check_status(sc: StatusCode, rate_limit: Option<&RateLimitInfo>) -> Result<(), PostalError> {
+    match sc {
+        StatusCode::OK => Ok(()),
+        StatusCode::INTERNAL_SERVER_ERROR => Err(PostalError::InternalServerError),
+        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT => {
+            Err(PostalError::ExpectedAlternativeUrl)
+        }
+        StatusCode::SERVICE_UNAVAILABLE => Err(PostalError::ServiceUnavailableError),
+        StatusCode::TOO_MANY_REQUESTS => Err(PostalError::RateLimited(
+            rate_limit.cloned().unwrap_or_default(),
+        )),
+        // postal itself answers with the statuses above,
+        // others come from a gateway in front of it or from a wrong path
+        status => Err(PostalError::UnexpectedStatus(status)),
+    }
+}
+
+async fn read_responce<T: DeserializeOwned>(
+    resp: reqwest::Response,
+    limit: Option<usize>,
+    rate_limit: Option<&RateLimitInfo>,
+) -> Result<(T, ResponseMeta), PostalError> {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(status = %resp.status(), "received a response from postal");
+
+    check_status(resp.status(), rate_limit)?;
+
+    let body = match limit {
+        Some(limit) => read_limited(resp, limit).await?,
+        None => resp.text().await?,
+    };
+    // a proxy in front of Postal may answer with e.g. an HTML page
+    let data: api_structures::Responce<T> = match serde_json::from_str(&body) {
+        Ok(data) => data,
+        Err(err) if err.is_syntax() || err.is_eof() => {
+            return Err(PostalError::Decode { body, source: err })
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    #[cfg(feature = "tracing")]
+    if let Some(time) = data.time() {
+        tracing::debug!(time, "postal processed the request");
+    }
+
+    let bytes_received = body.len();
+    let (data, meta) = check_responce(data, body)?;
+
+    Ok((
+        data,
+        ResponseMeta {
+            bytes_received,
+            ..meta
+        },
+    ))
+}
+
+/// Reads a body by chunks until it exceeds the limit
+async fn read_limited(mut resp: reqwest::Response, limit: usize) -> Result<String, PostalError> {
+    if resp.content_length().is_some_and(|len| len > limit as u64) {
+        return Err(PostalError::ResponseTooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(PostalError::ResponseTooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn check_responce<T>(
+    data: api_structures::Responce<T>,
+    raw: String,
+) -> Result<(T, ResponseMeta), PostalError> {
+    match data {
+        api_structures::Responce::Success(envelope) => Ok((
+            envelope.data,
+            ResponseMeta {
+                time: envelope.time,
+                flags: envelope.flags,
+                ..ResponseMeta::default()
+            },
+        )),
+        api_structures::Responce::Error(envelope) => Err(PostalError::Error {
+            code: envelope.data.code.into(),
+            message: envelope.data.message,
+            raw: Some(raw),
+        }),
+        api_structures::Responce::ParameterError(envelope) => Err(PostalError::Error {
+            code: PostalErrorCode::ParameterError,
+            message: envelope.data.message,
+            raw: Some(raw),
+        }),
+        api_structures::Responce::Unknown { status, body } => Err(PostalError::Error {
+            code: PostalErrorCode::Other(status),
+            message: body["data"]["message"]
+                .as_str()
+                .unwrap_or_default()
+                .to_owned(),
+            raw: Some(raw),
+        }),
+    }
+}
+
+/// MessageHash represents a hash which can be used to
+/// get a different information bout a message.
+pub type MessageHash = u64;
+
+/// The name of an environment variable [Client::from_env] reads a token from
+///
+/// [Client::from_env]: ./struct.Client.html#method.from_env
+pub const TOKEN_ENV: &str = "POSTAL_TOKEN";
+
+/// The name of a header Postal expects an API key in
+pub const API_KEY_HEADER: &str = "X-Server-API-Key";
+
+/// The maximum number of recipients Postal accepts
+/// in each of `to`, `cc` and `bcc` lists
+pub const MAX_RECIPIENTS: usize = 50;
+
+/// The tag [Client::send_test] sends messages with
+///
+/// [Client::send_test]: ./struct.Client.html#method.send_test
+pub const TEST_TAG: &str = "postal-rs-test";
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Message represents a email which can be sent
+///
+/// # Senders
+///
+/// `from` is the author of the message and `sender` is the one
+/// who actually sends it, when they differ.
+/// Postal authenticates the domain of `sender` if it's set
+/// and the domain of `from` otherwise.
+/// The return path of a message is always set by Postal.
+///
+/// RFC 5322 requires `sender` when `from` has several addresses.
+/// DMARC aligns on the domain of `from`, so with several authors
+/// at least one of them should be in a domain of `sender`.
+/// A client can refuse such a message without `sender`,
+/// see [ClientBuilder::require_sender].
+///
+/// [ClientBuilder::require_sender]: ./struct.ClientBuilder.html#method.require_sender
+///
+/// A message with `bounce` set is a bounce for another message,
+/// Postal sends it with an empty return path so it can't bounce back.
+///
+/// # Storing
+///
+/// A message can be stored as JSON e.g. in a job queue
+/// and loaded back to be sent later.
+///
+/// ```
+/// use postal_rs::{Attachment, Message};
+///
+/// let message = Message::default()
+///     .to(&["example@gmail.com".to_owned()])
+///     .from("test@yourserver.io")
+///     .subject("Hello World")
+///     .text("A test message")
+///     .header("X-Campaign", "spring")
+///     .attachment(Attachment::new("a.bin", "application/octet-stream", vec![0, 159, 255]));
+///
+/// let json = serde_json::to_string(&message).unwrap();
+/// let loaded: Message = serde_json::from_str(&json).unwrap();
+///
+/// assert_eq!(loaded, message);
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+pub struct Message {
+    ///The e-mail addresses of the recipients (max 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Vec<String>>,
+    /// The e-mail addresses of any CC contacts (max 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<Vec<String>>,
+    /// The e-mail addresses of any BCC contacts (max 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bcc: Option<Vec<String>>,
+    /// The e-mail address for the From header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The e-mail address for the Sender header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// The subject of the e-mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// The tag of the e-mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Set the reply-to address for the mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    /// The plain text body of the e-mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plain_body: Option<String>,
+    /// The HTML body of the e-mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+    /// An array of attachments for this e-mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<MessageAttachment>>,
+    /// Additional headers of the e-mail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    /// Is this message a bounce?
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounce: Option<bool>,
+    /// The charset of the bodies used when the message
+    /// is converted to a raw one, UTF-8 by default
+    ///
+    /// It's not a field of Postal's API, so it's never serialized.
+    /// A `charset` key is still read into it when a message is loaded.
+    #[serde(default, skip_serializing)]
+    pub charset: Option<String>,
+    /// Fields which are not modeled by the crate
+    ///
+    /// They are sent along with the other fields,
+    /// see [extra_field].
+    /// A key which is a name of a modeled field or `charset`
+    /// makes serialization fail with [PostalError::InvalidMessage].
+    ///
+    /// [extra_field]: #method.extra_field
+    /// [PostalError::InvalidMessage]: ./enum.PostalError.html#variant.InvalidMessage
+    #[serde(flatten)]
+    pub extra: HashMap<String, Json>,
+}
+
+impl Message {
+    /// Names of the fields the message is serialized with
+    const FIELDS: &'static [&'static str] = &[
+        "to",
+        "cc",
+        "bcc",
+        "from",
+        "sender",
+        "subject",
+        "tag",
+        "reply_to",
+        "plain_body",
+        "html_body",
+        "attachments",
+        "headers",
+        "bounce",
+    ];
+
+    /// Sets a field which is not modeled by the crate
+    ///
+    /// It allows to use a field a new version of Postal supports
+    /// before the crate does.
+    ///
+    /// It fails with [PostalError::InvalidMessage] if `key` is a name
+    /// of a modeled field or `charset`, the field itself should be set instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Message, PostalError};
+    ///
+    /// let message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .extra_field("track_opens", serde_json::json!(true))
+    ///     .unwrap();
+    ///
+    /// let json: serde_json::Value = serde_json::from_slice(&message.to_json().unwrap()).unwrap();
+    ///
+    /// assert_eq!(json["track_opens"], true);
+    /// assert_eq!(json["to"][0], "example@gmail.com");
+    /// assert_eq!(message.extra()["track_opens"], true);
+    ///
+    /// let err = message.extra_field("to", serde_json::json!(["a@gmail.com"])).unwrap_err();
+    /// assert!(matches!(err, PostalError::InvalidMessage(_)));
+    /// ```
+    ///
+    /// [PostalError::InvalidMessage]: ./enum.PostalError.html#variant.InvalidMessage
+    pub fn extra_field<K: Into<String>>(
+        mut self,
+        key: K,
+        value: Json,
+    ) -> Result<Self, PostalError> {
+        let key = key.into();
+        Self::check_extra_key(&key, WireProfile::Postal)?;
+
+        self.extra.insert(key, value);
+        Ok(self)
+    }
+
+    /// Checks that extra fields don't take keys of modeled ones
+    /// in the profile the message is serialized with
+    pub(crate) fn check_extra(&self, profile: WireProfile) -> Result<(), PostalError> {
+        self.extra
+            .keys()
+            .try_for_each(|key| Self::check_extra_key(key, profile))
+    }
+
+    fn check_extra_key(key: &str, profile: WireProfile) -> Result<(), PostalError> {
+        let is_modeled = key == "charset"
+            || Self::FIELDS
+                .iter()
+                .any(|field| *field == key || profile.key(field) == key);
+        if is_modeled {
+            return Err(PostalError::InvalidMessage(format!(
+                "`{}` is a field of a message, it can't be set as an extra one",
+                key
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns fields which are not modeled by the crate
+    ///
+    /// See [extra_field].
+    ///
+    /// [extra_field]: #method.extra_field
+    pub fn extra(&self) -> &HashMap<String, Json> {
+        &self.extra
+    }
+
+    /// Returns the number of recipients in `to`, `cc` and `bcc`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// assert_eq!(Message::default().recipient_count(), 0);
+    ///
+    /// let mut message = Message::default().to(&["a@gmail.com".to_owned(), "b@gmail.com".to_owned()]);
+    /// message.cc = Some(vec!["c@gmail.com".to_owned()]);
+    /// message.bcc = Some(vec!["d@gmail.com".to_owned()]);
+    /// assert_eq!(message.recipient_count(), 4);
+    /// ```
+    pub fn recipient_count(&self) -> usize {
+        [&self.to, &self.cc, &self.bcc]
+            .iter()
+            .map(|list| list.as_ref().map_or(0, Vec::len))
+            .sum()
+    }
+
+    /// Returns addresses of recipients in `to`, `cc` and `bcc`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// assert_eq!(Message::default().recipients().count(), 0);
+    ///
+    /// let mut message = Message::default().to(&["a@gmail.com".to_owned()]);
+    /// message.cc = Some(vec!["b@gmail.com".to_owned()]);
+    /// message.bcc = Some(vec!["c@gmail.com".to_owned()]);
+    /// assert_eq!(
+    ///     message.recipients().collect::<Vec<_>>(),
+    ///     ["a@gmail.com", "b@gmail.com", "c@gmail.com"]
+    /// );
+    /// ```
+    pub fn recipients(&self) -> impl Iterator<Item = &str> {
+        self.to
+            .iter()
+            .chain(&self.cc)
+            .chain(&self.bcc)
+            .flatten()
+            .map(String::as_str)
+    }
+
+    fn check(&self) -> Result<(), PostalError> {
+        let lists = [("to", &self.to), ("cc", &self.cc), ("bcc", &self.bcc)];
+        for (field, list) in lists.iter() {
+            let count = list.as_ref().map_or(0, Vec::len);
+            if count > MAX_RECIPIENTS {
+                return Err(PostalError::InvalidMessage(format!(
+                    "too many recipients in `{}` ({} > {})",
+                    field, count, MAX_RECIPIENTS
+                )));
+            }
+        }
+
+        if self.recipient_count() == 0 {
+            return Err(PostalError::InvalidMessage(
+                "no recipients were set".to_owned(),
+            ));
+        }
+
+        if self.from.is_none() {
+            return Err(PostalError::InvalidMessage(
+                "a `from` address is not set".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn from<S: Into<String>>(mut self, s: S) -> Self {
+        self.from = Some(s.into());
+        self
+    }
+
+    /// Sets a `from` address with a display name
+    ///
+    /// The name is quoted as RFC 5322 requires,
+    /// a non ASCII name is encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let message = Message::default()
+    ///     .from_with_name("Support", "support@example.com")
+    ///     .sender_with_name("Doe, John", "john@example.com")
+    ///     .reply_to_with_name("Jörg \"J\" Müller", "jorg@example.com");
+    ///
+    /// assert_eq!(message.from.as_deref(), Some("\"Support\" <support@example.com>"));
+    /// assert_eq!(message.sender.as_deref(), Some("\"Doe, John\" <john@example.com>"));
+    /// assert_eq!(
+    ///     message.reply_to.as_deref(),
+    ///     Some("=?utf-8?B?SsO2cmcgIkoiIE3DvGxsZXI=?= <jorg@example.com>")
+    /// );
+    ///
+    /// let message = Message::default().from_with_name("Say \"Hi\"", "hi@example.com");
+    /// assert_eq!(message.from.as_deref(), Some(r#""Say \"Hi\"" <hi@example.com>"#));
+    /// ```
+    pub fn from_with_name<N: AsRef<str>, A: AsRef<str>>(mut self, name: N, address: A) -> Self {
+        self.from = Some(address::with_name(name.as_ref(), address.as_ref()));
+        self
+    }
+
+    /// Sets a `sender` address with a display name
+    ///
+    /// See [from_with_name].
+    ///
+    /// [from_with_name]: #method.from_with_name
+    pub fn sender_with_name<N: AsRef<str>, A: AsRef<str>>(mut self, name: N, address: A) -> Self {
+        self.sender = Some(address::with_name(name.as_ref(), address.as_ref()));
+        self
+    }
+
+    /// Sets a `reply_to` address with a display name
+    ///
+    /// See [from_with_name].
+    ///
+    /// [from_with_name]: #method.from_with_name
+    pub fn reply_to_with_name<N: AsRef<str>, A: AsRef<str>>(mut self, name: N, address: A) -> Self {
+        self.reply_to = Some(address::with_name(name.as_ref(), address.as_ref()));
+        self
+    }
+
+    pub fn sender<S: Into<String>>(mut self, s: S) -> Self {
+        self.sender = Some(s.into());
+        self
+    }
+
+    pub fn bounce(mut self, bounce: bool) -> Self {
+        self.bounce = Some(bounce);
+        self
+    }
+
+    pub fn to(mut self, to: &[String]) -> Self {
+        self.to = Some(to.to_vec());
+        self
+    }
+
+    pub fn subject<S: Into<String>>(mut self, s: S) -> Self {
+        self.subject = Some(s.into());
+        self
+    }
+
+    /// Adds a header
+    ///
+    /// A header with the same name is replaced.
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.into());
+        self
+    }
+
+    /// Copies headers from a `HeaderMap` of the `http` crate
+    ///
+    /// Headers Postal builds from the fields of a message,
+    /// `From`, `Sender`, `To`, `Cc`, `Bcc`, `Reply-To` and `Subject`, are skipped.
+    /// So are values which are not valid UTF-8.
+    /// When a header has several values the last one is kept.
+    /// Names are lowercase as `HeaderMap` keeps them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    /// use reqwest::header::{HeaderMap, HeaderValue};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("X-Campaign", HeaderValue::from_static("spring"));
+    /// headers.insert("List-Unsubscribe", HeaderValue::from_static("<mailto:unsub@example.com>"));
+    /// headers.insert("Subject", HeaderValue::from_static("ignored"));
+    /// headers.insert("X-Binary", HeaderValue::from_bytes(b"\xff").unwrap());
+    ///
+    /// let message = Message::default().subject("Hi").headers_from(&headers);
+    /// let headers = message.headers.unwrap();
+    ///
+    /// assert_eq!(headers.len(), 2);
+    /// assert_eq!(headers["x-campaign"], "spring");
+    /// assert_eq!(headers["list-unsubscribe"], "<mailto:unsub@example.com>");
+    /// assert_eq!(message.subject.as_deref(), Some("Hi"));
+    /// ```
+    pub fn headers_from(mut self, headers: &reqwest::header::HeaderMap) -> Self {
+        const MANAGED: &[&str] = &["from", "sender", "to", "cc", "bcc", "reply-to", "subject"];
+
+        for (name, value) in headers {
+            if MANAGED.contains(&name.as_str()) {
+                continue;
+            }
+            if let Ok(value) = value.to_str() {
+                self = self.header(name.as_str(), value);
+            }
+        }
+        self
+    }
+
+    /// Sets headers which mark a priority of the message
+    ///
+    /// A message gets no priority headers unless it's called.
+    /// The headers with the same names set before are replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Message, Priority};
+    ///
+    /// let message = Message::default().priority(Priority::High);
+    /// let headers = message.headers.unwrap();
+    ///
+    /// assert_eq!(headers.len(), 3);
+    /// assert_eq!(headers["X-Priority"], "1 (Highest)");
+    /// assert_eq!(headers["Importance"], "high");
+    /// assert_eq!(headers["Priority"], "urgent");
+    ///
+    /// assert!(Message::default().headers.is_none());
+    /// ```
+    pub fn priority(mut self, priority: Priority) -> Self {
+        let names: Vec<&str> = priority.headers().iter().map(|(name, _)| *name).collect();
+        if let Some(headers) = &mut self.headers {
+            headers.retain(|name, _| !names.iter().any(|n| name.eq_ignore_ascii_case(n)));
+        }
+
+        for (name, value) in priority.headers().iter() {
+            self = self.header(*name, *value);
+        }
+        self
+    }
+
+    /// Sets the `Message-ID` header
+    ///
+    /// The id is wrapped into angle brackets if it's not already.
+    /// It's sent as a custom header, so whether it's kept is up to Postal.
+    /// Postal assigns its own `Message-ID` to a message sent by [Client::send]
+    /// and it may replace the custom one,
+    /// check it against a delivered message before relying on it for threading.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let message = Message::default()
+    ///     .header("message-id", "<old@example.com>")
+    ///     .message_id("1234@example.com");
+    ///
+    /// let json: serde_json::Value = serde_json::from_slice(&message.to_json().unwrap()).unwrap();
+    ///
+    /// assert_eq!(json["headers"]["Message-ID"], "<1234@example.com>");
+    /// assert!(json["headers"].get("message-id").is_none());
+    /// assert!(json.get("message_id").is_none());
+    /// ```
+    ///
+    /// [Client::send]: ./struct.Client.html#method.send
+    pub fn message_id<S: Into<String>>(mut self, id: S) -> Self {
+        let id = id.into();
+        let id = id.trim().trim_start_matches('<').trim_end_matches('>');
+        if let Some(headers) = &mut self.headers {
+            headers.retain(|name, _| !name.eq_ignore_ascii_case("Message-ID"));
+        }
+
+        self.header("Message-ID", format!("<{}>", id))
+    }
+
+    /// Adds an attachment
+    ///
+    /// An [EncodedAttachment] can be added as well
+    /// so its content isn't encoded on each send.
+    ///
+    /// [EncodedAttachment]: ./struct.EncodedAttachment.html
+    pub fn attachment<A: Into<MessageAttachment>>(mut self, attachment: A) -> Self {
+        self.attachments
+            .get_or_insert_with(Vec::new)
+            .push(attachment.into());
+        self
+    }
+
+    /// Returns an approximate size of the message in bytes
+    ///
+    /// It sums sizes of the addresses, the subject, the bodies, the headers
+    /// and the attachments, which are inflated by a third by base64.
+    /// Overhead of JSON or MIME formatting is not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Attachment, Message};
+    ///
+    /// let message = Message::default()
+    ///     .text("Hi")
+    ///     .attachment(Attachment::new("a.bin", "application/octet-stream", vec![0; 3000]));
+    ///
+    /// assert!(message.estimated_size() >= 4000);
+    /// ```
+    pub fn estimated_size(&self) -> usize {
+        let len = |value: &Option<String>| value.as_ref().map_or(0, String::len);
+
+        let addresses: usize = self.recipients().map(str::len).sum();
+        let texts = [
+            &self.from,
+            &self.sender,
+            &self.reply_to,
+            &self.subject,
+            &self.tag,
+            &self.plain_body,
+            &self.html_body,
+        ]
+        .iter()
+        .map(|value| len(value))
+        .sum::<usize>();
+        let headers: usize = self
+            .headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| name.len() + value.len())
+            .sum();
+        let attachments: usize = self
+            .attachments
+            .iter()
+            .flatten()
+            .map(|attachment| {
+                attachment.name().len() + attachment.content_type().len() + attachment.encoded_len()
+            })
+            .sum();
+
+        addresses + texts + headers + attachments
+    }
 
-        handle_send(res).await
+    /// Checks whether `from` has several addresses but `sender` is not set
+    fn needs_sender(&self) -> bool {
+        self.sender.is_none()
+            && self
+                .from
+                .as_deref()
+                .is_some_and(|from| address::split_list(from).len() > 1)
     }
 
-    /// Sends a standart SMTP message to Postal
-    pub async fn send_raw<M: Into<RawMessage>>(
-        &self,
-        message: M,
-    ) -> Result<Vec<SendResult>, PostalError> {
-        let address = self.address.join("/api/v1/send/raw")?;
-        let message = message.into();
+    /// Removes all recipients from `to`, `cc` and `bcc`
+    ///
+    /// It allows a message to be reused as a template.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let template = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .subject("Hello World")
+    ///     .text("A test message");
+    ///
+    /// let message = template.clear_recipients();
+    ///
+    /// assert_eq!(message.recipient_count(), 0);
+    /// assert_eq!(message.subject.as_deref(), Some("Hello World"));
+    /// assert_eq!(message.plain_body.as_deref(), Some("A test message"));
+    /// ```
+    pub fn clear_recipients(mut self) -> Self {
+        self.to = None;
+        self.cc = None;
+        self.bcc = None;
+        self
+    }
 
-        let client = reqwest::Client::new();
-        let res = client
-            .post(address)
-            .json(&message)
-            .header("X-Server-API-Key", &self.token)
-            .send()
-            .await?;
+    /// Removes all attachments
+    pub fn clear_attachments(mut self) -> Self {
+        self.attachments = None;
+        self
+    }
 
-        handle_send(res).await
+    /// Removes all additional headers
+    pub fn clear_headers(mut self) -> Self {
+        self.headers = None;
+        self
     }
 
-    /// Asks a Postal server to provide an information details
-    /// about a message
+    /// Prepends a prefix like `[STAGING]` to the subject
     ///
-    /// By default it provides a limited information.
-    /// To increase this volume you can specify expansions via [DetailsInterest]
+    /// The prefix and the subject are separated by a single space.
+    /// If there's no subject the prefix becomes the subject.
     ///
-    /// [DetailsInterest]: ./struct.DetailsInterest.html
-    pub async fn get_message_details<I: Into<DetailsInterest>>(
-        &self,
-        interest: I,
-    ) -> Result<HashMap<String, Json>, PostalError> {
-        let interest = interest.into();
-        let address = self.address.join("/api/v1/messages/message")?;
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let message = Message::default().subject("Welcome").prefix_subject("[STAGING]");
+    /// assert_eq!(message.subject.as_deref(), Some("[STAGING] Welcome"));
+    ///
+    /// let message = Message::default().subject("  Welcome").prefix_subject("[STAGING] ");
+    /// assert_eq!(message.subject.as_deref(), Some("[STAGING] Welcome"));
+    ///
+    /// let message = Message::default().prefix_subject("[STAGING]");
+    /// assert_eq!(message.subject.as_deref(), Some("[STAGING]"));
+    ///
+    /// let message = Message::default().subject("").prefix_subject("[STAGING]");
+    /// assert_eq!(message.subject.as_deref(), Some("[STAGING]"));
+    /// ```
+    pub fn prefix_subject<S: AsRef<str>>(mut self, prefix: S) -> Self {
+        let prefix = prefix.as_ref().trim_end();
+        let subject = match self.subject.as_deref().map(str::trim_start) {
+            Some(subject) if !subject.is_empty() => format!("{} {}", prefix, subject),
+            _ => prefix.to_owned(),
+        };
 
-        let client = reqwest::Client::new();
-        let body: Json = interest.into();
-        let res = client
-            .post(address)
-            .json(&body)
-            .header("X-Server-API-Key", &self.token)
-            .send()
-            .await?;
+        self.subject = Some(subject);
+        self
+    }
 
-        check_status(res.status())?;
+    pub fn text<S: Into<String>>(mut self, s: S) -> Self {
+        self.plain_body = Some(s.into());
+        self
+    }
 
-        let data: api_structures::Responce<HashMap<String, Json>> = res.json().await?;
-        let data = check_responce(data)?;
+    pub fn html<S: Into<String>>(mut self, s: S) -> Self {
+        self.html_body = Some(s.into());
+        self
+    }
 
-        Ok(data)
+    /// Serializes the message into the JSON body
+    /// [Client::send] would send
+    ///
+    /// It fails with [PostalError::InvalidMessage] if an extra field
+    /// has a key of a modeled one.
+    ///
+    /// [Client::send]: ./struct.Client.html#method.send
+    /// [PostalError::InvalidMessage]: ./enum.PostalError.html#variant.InvalidMessage
+    pub fn to_json(&self) -> Result<Vec<u8>, PostalError> {
+        self.check_extra(WireProfile::Postal)?;
+        Ok(serde_json::to_vec(self)?)
     }
 
-    /// Obtains a delivery information according to a message.
-    pub async fn get_message_deliveries(
-        &self,
-        id: MessageHash,
-    ) -> Result<Vec<HashMap<String, Json>>, PostalError> {
-        let address = self.address.join("/api/v1/messages/deliveries")?;
+    /// Serializes the message into a pretty printed JSON
+    ///
+    /// It's meant for logging and debugging,
+    /// fields which are not set are omitted as in [to_json].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let message = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .subject("Hello World");
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(message.to_json().unwrap()).unwrap(),
+    ///     r#"{"to":["example@gmail.com"],"subject":"Hello World"}"#
+    /// );
+    /// assert_eq!(
+    ///     message.to_json_pretty().unwrap(),
+    ///     "{\n  \"to\": [\n    \"example@gmail.com\"\n  ],\n  \"subject\": \"Hello World\"\n}"
+    /// );
+    /// ```
+    ///
+    /// [to_json]: #method.to_json
+    pub fn to_json_pretty(&self) -> Result<String, PostalError> {
+        self.check_extra(WireProfile::Postal)?;
+        Ok(serde_json::to_string_pretty(self)?)
+    }
 
-        let client = reqwest::Client::new();
-        let body: Json = serde_json::json!({ "id": id });
-        let res = client
-            .post(address)
-            .json(&body)
-            .header("X-Server-API-Key", &self.token)
-            .send()
-            .await?;
+    /// Sets a charset of the bodies
+    ///
+    /// Postal itself always treats bodies as UTF-8,
+    /// the charset only affects a message built by [into_raw_message]
+    /// and it's not sent along with the message.
+    ///
+    /// [into_raw_message]: #method.into_raw_message
+    pub fn charset<S: Into<String>>(mut self, charset: S) -> Self {
+        self.charset = Some(charset.into());
+        self
+    }
 
-        check_status(res.status())?;
+    /// Builds an RFC2822 message out of this one
+    ///
+    /// The bodies are encoded in the message charset,
+    /// a charset which can't be used in a message e.g. `utf-16` is replaced by UTF-8.
+    /// A body which contains only ASCII characters is sent with `7bit`
+    /// transfer encoding, the others are encoded by `base64`,
+    /// as well as a body with a line longer than 998 characters.
+    /// When both a plain and an HTML bodies are set
+    /// they are put into a `multipart/alternative` part.
+    /// Attachments are added as `multipart/mixed` parts,
+    /// inline ones get `Content-Disposition: inline` and their `Content-ID`.
+    /// With attachments the `multipart/alternative` part, if any,
+    /// becomes the first part of the `multipart/mixed` one,
+    /// so mail clients show only one of the bodies.
+    ///
+    /// Additional headers are written after the ones built from the fields,
+    /// except headers with the same names as the built ones.
+    ///
+    /// `sender`, or `from` if it's not set, becomes `mail_from`.
+    /// Recipients from `to`, `cc` and `bcc` become `rcpt_to`.
+    /// A `bcc` is not mentioned in the headers.
+    ///
+    /// It fails with [PostalError::InvalidMessage] if a header value
+    /// has a line break or a body can't be encoded in the charset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Attachment, Message, PostalError};
+    ///
+    /// let raw = Message::default()
+    ///     .to(&["example@gmail.com".to_owned()])
+    ///     .from("test@yourserver.io")
+    ///     .text("A test message")
+    ///     .html("<p>A test message</p>")
+    ///     .attachment(Attachment::new("a.txt", "text/plain", "data"))
+    ///     .into_raw_message()
+    ///     .unwrap();
+    /// let data = String::from_utf8(base64::decode(&raw.data).unwrap()).unwrap();
+    /// assert!(data.starts_with("From: test@yourserver.io\r\nTo: example@gmail.com\r\n"));
+    ///
+    /// let boundary = |content_type: &str| {
+    ///     let start = data.find(&format!("Content-Type: {}; boundary=\"", content_type))?;
+    ///     let value = data[start..].split('"').nth(1)?;
+    ///     Some(format!("--{}", value))
+    /// };
+    /// let mixed = boundary("multipart/mixed").unwrap();
+    /// let alternative = boundary("multipart/alternative").unwrap();
+    ///
+    /// let parts: Vec<&str> = data.split(mixed.as_str()).collect();
+    /// // a preamble, the bodies, the attachment and an epilogue
+    /// assert_eq!(parts.len(), 4);
+    /// assert!(parts[1].trim_start().starts_with("Content-Type: multipart/alternative"));
+    /// let bodies: Vec<&str> = parts[1].split(alternative.as_str()).collect();
+    /// assert!(bodies[1].trim_start().starts_with("Content-Type: text/plain"));
+    /// assert!(bodies[2].trim_start().starts_with("Content-Type: text/html"));
+    /// assert!(parts[2].contains("Content-Disposition: attachment; filename=\"a.txt\""));
+    ///
+    /// let err = Message::default()
+    ///     .from("test@yourserver.io")
+    ///     .subject("Hi\r\nBcc: evil@example.com")
+    ///     .into_raw_message()
+    ///     .unwrap_err();
+    /// assert!(matches!(err, PostalError::InvalidMessage(_)));
+    /// ```
+    ///
+    /// [PostalError::InvalidMessage]: ./enum.PostalError.html#variant.InvalidMessage
+    pub fn into_raw_message(self) -> Result<RawMessage, PostalError> {
+        let from = self
+            .sender
+            .as_deref()
+            .or(self.from.as_deref())
+            .ok_or_else(|| PostalError::InvalidMessage("a `from` address is not set".to_owned()))?;
 
-        let data: api_structures::Responce<Vec<HashMap<String, Json>>> = res.json().await?;
-        let data = check_responce(data)?;
+        let data = mime::build(&self)?;
+        let mut raw = RawMessage::new(
+            &mime::recipients(&self),
+            address::mailbox(from),
+            base64::encode(data),
+        );
+        raw.bounce = self.bounce;
 
-        Ok(data)
+        Ok(raw)
     }
-}
-
-async fn handle_send(resp: reqwest::Response) -> Result<Vec<SendResult>, PostalError> {
-    check_status(resp.status())?;
 
-    let data: api_structures::Responce<api_structures::MessageSucessData> = resp.json().await?;
-    let data = check_responce(data)?;
+    /// Checks a message and reports all problems found
+    ///
+    /// It checks the same rules as [MessageBuilder::build]
+    /// and that addresses look like `local@domain`,
+    /// see [validate_addresses].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::{Message, MessageValidationError};
+    ///
+    /// let errors = Message::default().to(&["not an address".to_owned()]).validate().unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     errors,
+    ///     vec![
+    ///         MessageValidationError::MissingFrom,
+    ///         MessageValidationError::MissingBody,
+    ///         MessageValidationError::InvalidAddress {
+    ///             field: "to",
+    ///             value: "not an address".to_owned()
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [MessageBuilder::build]: ./struct.MessageBuilder.html#method.build
+    /// [validate_addresses]: #method.validate_addresses
+    pub fn validate(&self) -> Result<(), Vec<MessageValidationError>> {
+        let mut errors = Vec::new();
 
-    let messages = data
-        .messages
-        .into_iter()
-        .map(|(to, m)| SendResult { to, id: m.id })
-        .collect();
+        let lists = [("to", &self.to), ("cc", &self.cc), ("bcc", &self.bcc)];
+        for (field, list) in lists.iter() {
+            let count = list.as_ref().map_or(0, Vec::len);
+            if count > MAX_RECIPIENTS {
+                errors.push(MessageValidationError::TooManyRecipients { field, count });
+            }
+        }
 
-    Ok(messages)
-}
+        if self.recipient_count() == 0 {
+            errors.push(MessageValidationError::NoRecipients);
+        }
 
-fn check_status(sc: StatusCode) -> Result<(), PostalError> {
-    match sc {
-        StatusCode::OK => Ok(()),
-        StatusCode::INTERNAL_SERVER_ERROR => Err(PostalError::InternalServerError),
-        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT => {
-            Err(PostalError::ExpectedAlternativeUrl)
+        if self.from.is_none() {
+            errors.push(MessageValidationError::MissingFrom);
         }
-        StatusCode::SERVICE_UNAVAILABLE => Err(PostalError::ServiceUnavailableError),
-        // according to postal docs it's imposible to get a different status code
-        // https://krystal.github.io/postal-api/index.html
-        _ => unreachable!(),
-    }
-}
 
-fn check_responce<T>(data: api_structures::Responce<T>) -> Result<T, PostalError> {
-    match data {
-        api_structures::Responce::Success { data, .. } => Ok(data),
-        api_structures::Responce::Error { data, .. } => Err(PostalError::Error {
-            code: data.code,
-            message: data.message,
-        }),
-        // the format of this error is unclear
-        api_structures::Responce::ParameterError {} => unimplemented!(),
-    }
-}
+        if self.plain_body.is_none() && self.html_body.is_none() {
+            errors.push(MessageValidationError::MissingBody);
+        }
 
-/// MessageHash represents a hash which can be used to
-/// get a different information bout a message.
-pub type MessageHash = u64;
+        if self.needs_sender() {
+            errors.push(MessageValidationError::MissingSender);
+        }
 
-/// Message represents a email which can be sent
-#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
-pub struct Message {
-    ///The e-mail addresses of the recipients (max 50)
-    pub to: Option<Vec<String>>,
-    /// The e-mail addresses of any CC contacts (max 50)
-    pub cc: Option<Vec<String>>,
-    /// The e-mail addresses of any BCC contacts (max 50)
-    pub bcc: Option<Vec<String>>,
-    /// The e-mail address for the From header
-    pub from: Option<String>,
-    /// The e-mail address for the Sender header
-    pub sender: Option<String>,
-    /// The subject of the e-mail
-    pub subject: Option<String>,
-    /// The tag of the e-mail
-    pub tag: Option<String>,
-    /// Set the reply-to address for the mail
-    pub reply_to: Option<String>,
-    /// The plain text body of the e-mail
-    pub plain_body: Option<String>,
-    /// The HTML body of the e-mail
-    pub html_body: Option<String>,
-    /// An array of attachments for this e-mail
-    pub attachments: Option<Vec<Vec<u8>>>,
-    /// A hash of additional headers
-    pub headers: Option<MessageHash>,
-    /// Is this message a bounce?
-    pub bounce: Option<bool>,
-}
+        let addresses = lists
+            .iter()
+            .flat_map(|(field, list)| list.iter().flatten().map(move |value| (*field, value)))
+            .chain(self.from.iter().map(|value| ("from", value)))
+            .chain(self.reply_to.iter().map(|value| ("reply_to", value)));
+        for (field, value) in addresses {
+            if address::normalize(value).is_none() {
+                errors.push(MessageValidationError::InvalidAddress {
+                    field,
+                    value: value.clone(),
+                });
+            }
+        }
 
-impl Message {
-    pub fn from<S: Into<String>>(mut self, s: S) -> Self {
-        self.from = Some(s.into());
-        self
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
-    pub fn to(mut self, to: &[String]) -> Self {
-        self.to = Some(to.to_vec());
+    /// Removes repeated recipients
+    ///
+    /// An address is kept only in the first of `to`, `cc` and `bcc`
+    /// it's found in, and only once there.
+    /// Addresses are compared ignoring display names
+    /// and the case of the domain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let mut message = Message::default().to(&["a@gmail.com".to_owned()]);
+    /// message.cc = Some(vec!["A <a@GMAIL.com>".to_owned(), "b@gmail.com".to_owned()]);
+    /// message.bcc = Some(vec!["b@Gmail.com".to_owned(), "c@gmail.com".to_owned(), "c@gmail.com".to_owned()]);
+    ///
+    /// let message = message.dedup_recipients();
+    ///
+    /// assert_eq!(message.to.unwrap(), ["a@gmail.com"]);
+    /// assert_eq!(message.cc.unwrap(), ["b@gmail.com"]);
+    /// assert_eq!(message.bcc.unwrap(), ["c@gmail.com"]);
+    /// ```
+    pub fn dedup_recipients(mut self) -> Self {
+        let mut seen = HashSet::new();
+        let lists = self.to.iter_mut().chain(&mut self.cc).chain(&mut self.bcc);
+        for list in lists {
+            list.retain(|address| seen.insert(address::mailbox_key(address)));
+        }
+
         self
     }
 
-    pub fn subject<S: Into<String>>(mut self, s: S) -> Self {
-        self.subject = Some(s.into());
-        self
+    /// Removes recipients for which `is_suppressed` returns true
+    /// and returns them
+    ///
+    /// `is_suppressed` is called with addresses without display names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::Message;
+    ///
+    /// let mut message = Message::default().to(&["a@gmail.com".to_owned()]);
+    /// message.cc = Some(vec!["B <b@gmail.com>".to_owned()]);
+    ///
+    /// let removed = message.remove_suppressed(|to| to == "b@gmail.com");
+    ///
+    /// assert_eq!(removed, ["B <b@gmail.com>"]);
+    /// assert_eq!(message.recipients().collect::<Vec<_>>(), ["a@gmail.com"]);
+    /// ```
+    pub fn remove_suppressed<F: Fn(&str) -> bool>(&mut self, is_suppressed: F) -> Vec<String> {
+        let mut removed = Vec::new();
+        let lists = self.to.iter_mut().chain(&mut self.cc).chain(&mut self.bcc);
+        for list in lists {
+            let (suppressed, kept): (Vec<String>, Vec<String>) = list
+                .drain(..)
+                .partition(|to| is_suppressed(address::mailbox(to)));
+            *list = kept;
+            removed.extend(suppressed);
+        }
+
+        removed
     }
 
-    pub fn text<S: Into<String>>(mut self, s: S) -> Self {
-        self.plain_body = Some(s.into());
-        self
+    /// Trims whitespaces around addresses and checks that
+    /// each of them looks like `local@domain`.
+    ///
+    /// It checks `to`, `cc`, `bcc`, `from` and `reply_to` fields.
+    ///
+    /// With the `idna` feature enabled, domains of the addresses
+    /// are converted to their ASCII (punycode) form,
+    /// e.g. `user@münchen.de` becomes `user@xn--mnchen-3ya.de`.
+    pub fn validate_addresses(mut self) -> Result<Self, PostalError> {
+        fn normalize(field: &'static str, value: &str) -> Result<String, PostalError> {
+            address::normalize(value).ok_or_else(|| PostalError::InvalidAddress {
+                field,
+                value: value.to_owned(),
+            })
+        }
+
+        let lists = [
+            ("to", &mut self.to),
+            ("cc", &mut self.cc),
+            ("bcc", &mut self.bcc),
+        ];
+        for (field, list) in lists {
+            for address in list.iter_mut().flatten() {
+                *address = normalize(field, address)?;
+            }
+        }
+
+        if let Some(from) = &mut self.from {
+            *from = normalize("from", from)?;
+        }
+
+        if let Some(reply_to) = &mut self.reply_to {
+            *reply_to = normalize("reply_to", reply_to)?;
+        }
+
+        Ok(self)
     }
+}
 
-    pub fn html<S: Into<String>>(mut self, s: S) -> Self {
-        self.html_body = Some(s.into());
-        self
+/// It prints a short summary of the message.
+///
+/// Bodies and attachments are never printed.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |list: &Option<Vec<String>>| list.as_ref().map_or(0, Vec::len);
+
+        write!(
+            f,
+            "{:?} to {} recipient(s), cc {}, bcc {}",
+            self.subject.as_deref().unwrap_or_default(),
+            count(&self.to),
+            count(&self.cc),
+            count(&self.bcc),
+        )
     }
 }
 
@@ -278,6 +2677,87 @@ impl RawMessage {
             bounce: None,
         }
     }
+
+    /// Constructs a message from an RFC2822 (`.eml`) file
+    ///
+    /// See [from_eml_bytes].
+    ///
+    /// [from_eml_bytes]: #method.from_eml_bytes
+    pub fn from_eml_file<P: AsRef<Path>>(
+        path: P,
+        rcpt_to: Option<&[String]>,
+        mail_from: Option<&str>,
+    ) -> Result<Self, PostalError> {
+        let data = fs::read(path)?;
+
+        Self::from_eml_bytes(&data, rcpt_to, mail_from)
+    }
+
+    /// Constructs a message from an RFC2822 message
+    ///
+    /// If `rcpt_to` is not given, the recipients are taken
+    /// from `To`, `Cc` and `Bcc` headers.
+    /// If `mail_from` is not given, it's taken from
+    /// `Return-Path`, `Sender` or `From` header, whichever is found first.
+    ///
+    /// A null `Return-Path: <>` is not a sender, it marks a bounce,
+    /// so the message is sent as a bounce and `mail_from` is taken
+    /// from the other headers.
+    pub fn from_eml_bytes(
+        data: &[u8],
+        rcpt_to: Option<&[String]>,
+        mail_from: Option<&str>,
+    ) -> Result<Self, PostalError> {
+        let headers = mime::parse_headers(data);
+        let header = |name: &'static str| {
+            headers
+                .iter()
+                .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+
+        let rcpt_to = match rcpt_to {
+            Some(rcpt_to) => rcpt_to.to_vec(),
+            None => header("To")
+                .chain(header("Cc"))
+                .chain(header("Bcc"))
+                .flat_map(address::split_list)
+                .map(|to| address::mailbox(to).to_owned())
+                .collect(),
+        };
+        if rcpt_to.is_empty() {
+            return Err(PostalError::InvalidMessage(
+                "no recipients were found".to_owned(),
+            ));
+        }
+
+        let mail_from = match mail_from {
+            Some(mail_from) => mail_from.to_owned(),
+            None => header("Return-Path")
+                .chain(header("Sender"))
+                .chain(header("From"))
+                .flat_map(address::split_list)
+                .map(address::mailbox)
+                .find(|from| !from.is_empty())
+                .ok_or_else(|| PostalError::InvalidMessage("a sender was not found".to_owned()))?
+                .to_owned(),
+        };
+
+        let mut message = Self::new(&rcpt_to, mail_from, base64::encode(data));
+        if header("Return-Path").any(|path| address::mailbox(path).is_empty()) {
+            message.bounce = Some(true);
+        }
+
+        Ok(message)
+    }
+
+    /// Serializes the message into the JSON body
+    /// [Client::send_raw] would send
+    ///
+    /// [Client::send_raw]: ./struct.Client.html#method.send_raw
+    pub fn to_json(&self) -> Result<Vec<u8>, PostalError> {
+        Ok(serde_json::to_vec(self)?)
+    }
 }
 
 /// DetailsInterest contains an options which can be used to
@@ -325,6 +2805,11 @@ impl DetailsInterest {
         self
     }
 
+    pub fn with_attachments(mut self) -> Self {
+        self.attachments = Some(());
+        self
+    }
+
     pub fn with_headers(mut self) -> Self {
         self.headers = Some(());
         self
@@ -372,6 +2857,13 @@ impl DetailsInterest {
                 .unwrap()
                 .push(Json::String("html_body".to_owned()));
         }
+        if self.attachments.is_some() {
+            expansions = Some(expansions.unwrap_or_default());
+            expansions
+                .as_mut()
+                .unwrap()
+                .push(Json::String("attachments".to_owned()));
+        }
         if self.headers.is_some() {
             expansions = Some(expansions.unwrap_or_default());
             expansions
@@ -391,10 +2883,10 @@ impl DetailsInterest {
     }
 }
 
-impl Into<DetailsInterest> for MessageHash {
-    fn into(self) -> DetailsInterest {
+impl From<MessageHash> for DetailsInterest {
+    fn from(id: MessageHash) -> Self {
         DetailsInterest {
-            id: self,
+            id,
             status: None,
             details: None,
             inspection: None,
@@ -407,12 +2899,12 @@ impl Into<DetailsInterest> for MessageHash {
     }
 }
 
-impl Into<Json> for DetailsInterest {
-    fn into(self) -> Json {
+impl From<DetailsInterest> for Json {
+    fn from(interest: DetailsInterest) -> Self {
         let mut map: HashMap<String, Json> = HashMap::new();
-        map.insert("id".to_owned(), self.id.into());
+        map.insert("id".to_owned(), interest.id.into());
 
-        let expansions = self.build_expansions_list();
+        let expansions = interest.build_expansions_list();
         if let Some(expansions) = expansions {
             map.insert("_expansions".to_owned(), Json::Array(expansions));
         }
@@ -421,33 +2913,197 @@ impl Into<Json> for DetailsInterest {
     }
 }
 
+/// MessageReport contains all known information about a message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageReport {
+    /// Details of the message
+    pub details: MessageDetails,
+    /// Deliveries of the message
+    pub deliveries: Vec<Delivery>,
+}
+
 /// SendResult represent a result of sending request
-#[derive(Debug, Eq, PartialEq, Clone)]
+///
+/// # Examples
+///
+/// The token of a message is sent in webhooks about it,
+/// so they can be correlated with the sent message.
+///
+/// ```
+/// # #[cfg(feature = "testing")]
+/// use postal_rs::testing::MockPostal;
+/// use postal_rs::Message;
+/// # #[cfg(feature = "testing")]
+/// use serde_json::json;
+///
+/// # #[cfg(feature = "testing")]
+/// #[tokio::main]
+/// async fn main() {
+///     let mock = MockPostal::start().await;
+///     mock.respond_success(
+///         "/api/v1/send/message",
+///         json!({
+///             "message_id": "message@postal",
+///             "messages": {
+///                 "a@example.com": { "id": 1, "token": "tokenA" },
+///                 "b@example.com": { "id": 2, "token": "tokenB" }
+///             }
+///         }),
+///     );
+///     let message = Message::default()
+///         .to(&["a@example.com".to_owned(), "b@example.com".to_owned()])
+///         .from("test@yourserver.io")
+///         .text("A test message");
+///
+///     let mut results = mock.client().send(message).await.unwrap();
+///     results.sort_by_key(|result| result.id);
+///
+///     assert_eq!(results[0].to, "a@example.com");
+///     assert_eq!(results[0].token, "tokenA");
+///     assert_eq!(results[1].to, "b@example.com");
+///     assert_eq!(results[1].token, "tokenB");
+/// }
+/// # #[cfg(not(feature = "testing"))]
+/// # fn main() {}
+/// ```
+///
+/// Results are ordered and hashed by the message id,
+/// so they can be kept in collections.
+///
+/// ```
+/// use postal_rs::SendResult;
+/// use std::collections::{BTreeSet, HashSet};
+///
+/// let result = |to: &str, id| SendResult { to: to.to_owned(), id, token: format!("t{}", id) };
+/// let results = vec![result("c@example.com", 3), result("a@example.com", 1), result("b@example.com", 2)];
+///
+/// let ordered: BTreeSet<SendResult> = results.iter().cloned().collect();
+/// let ids: Vec<_> = ordered.iter().map(|result| result.id).collect();
+/// assert_eq!(ids, [1, 2, 3]);
+///
+/// let mut unique: HashSet<SendResult> = results.into_iter().collect();
+/// assert!(!unique.insert(result("a@example.com", 1)));
+/// assert_eq!(unique.len(), 3);
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 pub struct SendResult {
     /// An email To which an email was sent
     pub to: String,
     /// A message id which can be used to retrieve message details
     /// and message deliveries
     pub id: MessageHash,
+    /// A message token which webhooks about the message refer to
+    #[serde(default)]
+    pub token: String,
+}
+
+/// SendOutcome represent a result of sending request
+/// with a track of recipients which were not accepted
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+pub struct SendOutcome {
+    /// Results for recipients which were accepted by Postal
+    pub accepted: Vec<SendResult>,
+    /// Requested recipients which didn't get a message id
+    pub missing: Vec<String>,
+}
+
+impl SendOutcome {
+    /// Checks whether all recipients were accepted
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+impl Hash for SendResult {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Ord for SendResult {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.id, &self.to, &self.token).cmp(&(other.id, &other.to, &other.token))
+    }
+}
+
+impl PartialOrd for SendResult {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for SendResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> #{}", self.to, self.id)
+    }
 }
 
 mod api_structures {
     use super::*;
+    use serde::{de::Error, Deserializer};
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    #[serde(tag = "status", rename_all = "camelCase")]
+    /// Responce is a body of any Postal response
+    ///
+    /// A response with an unknown status is kept as is
+    /// so a new status doesn't break parsing.
+    #[derive(Debug, Clone)]
     pub enum Responce<D> {
-        Success {
-            time: f64,
-            flags: HashMap<String, u64>,
-            data: D,
-        },
-        ParameterError {},
-        Error {
-            time: f64,
-            flags: HashMap<String, u64>,
-            data: ResponceError,
-        },
+        Success(Envelope<D>),
+        ParameterError(Envelope<ParameterError>),
+        Error(Envelope<ResponceError>),
+        Unknown { status: String, body: Json },
+    }
+
+    impl<'de, D: DeserializeOwned> Deserialize<'de> for Responce<D> {
+        fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+            fn parse<T: DeserializeOwned, E: Error>(
+                body: serde_json::Map<String, Json>,
+            ) -> Result<T, E> {
+                serde_json::from_value(Json::Object(body)).map_err(E::custom)
+            }
+
+            let mut body = serde_json::Map::<String, Json>::deserialize(deserializer)?;
+            let status = match body.remove("status") {
+                Some(Json::String(status)) => status,
+                _ => String::new(),
+            };
+
+            match status.as_str() {
+                "success" => parse(body).map(Self::Success),
+                "error" => parse(body).map(Self::Error),
+                "parameter-error" => parse(body).map(Self::ParameterError),
+                _ => Ok(Self::Unknown {
+                    status,
+                    body: Json::Object(body),
+                }),
+            }
+        }
+    }
+
+    impl<D> Responce<D> {
+        #[cfg(feature = "tracing")]
+        pub fn time(&self) -> Option<f64> {
+            match self {
+                Self::Success(envelope) => Some(envelope.time),
+                Self::ParameterError(envelope) => Some(envelope.time),
+                Self::Error(envelope) => Some(envelope.time),
+                Self::Unknown { body, .. } => body["time"].as_f64(),
+            }
+        }
+    }
+
+    /// Envelope holds fields common for all responses
+    ///
+    /// Fields which are not known are kept in `extra`.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Envelope<D> {
+        #[serde(default)]
+        pub time: f64,
+        #[serde(default)]
+        pub flags: ResponseFlags,
+        pub data: D,
+        #[serde(flatten)]
+        pub extra: HashMap<String, Json>,
     }
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -467,4 +3123,10 @@ mod api_structures {
         pub code: String,
         pub message: String,
     }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ParameterError {
+        #[serde(default)]
+        pub message: String,
+    }
 }
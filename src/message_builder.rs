@@ -0,0 +1,106 @@
+use crate::{Message, MessageAttachment, PostalError};
+
+/// MessageBuilder builds a [Message] checking that it can be sent
+///
+/// Unlike building a [Message] directly,
+/// [build] fails if there's no recipients, no `from` address or no body.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::MessageBuilder;
+///
+/// let message = MessageBuilder::new()
+///     .to(&["example@gmail.com".to_owned()])
+///     .from("test@yourserver.io")
+///     .subject("Hello World")
+///     .text("A test message")
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// [Message]: ./struct.Message.html
+/// [build]: #method.build
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    message: Message,
+}
+
+impl MessageBuilder {
+    /// Constructs an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn to(mut self, to: &[String]) -> Self {
+        self.message = self.message.to(to);
+        self
+    }
+
+    pub fn cc(mut self, cc: &[String]) -> Self {
+        self.message.cc = Some(cc.to_vec());
+        self
+    }
+
+    pub fn bcc(mut self, bcc: &[String]) -> Self {
+        self.message.bcc = Some(bcc.to_vec());
+        self
+    }
+
+    pub fn from<S: Into<String>>(mut self, s: S) -> Self {
+        self.message = self.message.from(s);
+        self
+    }
+
+    pub fn sender<S: Into<String>>(mut self, s: S) -> Self {
+        self.message = self.message.sender(s);
+        self
+    }
+
+    pub fn reply_to<S: Into<String>>(mut self, s: S) -> Self {
+        self.message.reply_to = Some(s.into());
+        self
+    }
+
+    pub fn subject<S: Into<String>>(mut self, s: S) -> Self {
+        self.message = self.message.subject(s);
+        self
+    }
+
+    pub fn tag<S: Into<String>>(mut self, s: S) -> Self {
+        self.message.tag = Some(s.into());
+        self
+    }
+
+    pub fn text<S: Into<String>>(mut self, s: S) -> Self {
+        self.message = self.message.text(s);
+        self
+    }
+
+    pub fn html<S: Into<String>>(mut self, s: S) -> Self {
+        self.message = self.message.html(s);
+        self
+    }
+
+    pub fn attachment<A: Into<MessageAttachment>>(mut self, attachment: A) -> Self {
+        self.message = self.message.attachment(attachment);
+        self
+    }
+
+    /// Builds a message
+    ///
+    /// It fails if there's no recipients, if there's more then [MAX_RECIPIENTS]
+    /// in any of `to`, `cc`, `bcc`, if `from` is not set
+    /// or if there's neither a plain nor an HTML body.
+    ///
+    /// [MAX_RECIPIENTS]: ./constant.MAX_RECIPIENTS.html
+    pub fn build(self) -> Result<Message, PostalError> {
+        self.message.check()?;
+
+        if self.message.plain_body.is_none() && self.message.html_body.is_none() {
+            return Err(PostalError::InvalidMessage("no body was set".to_owned()));
+        }
+
+        Ok(self.message)
+    }
+}
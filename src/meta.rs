@@ -0,0 +1,134 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use std::{collections::HashMap, time::SystemTime};
+
+/// ResponseMeta holds information Postal sends along with a result
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMeta {
+    /// Time in seconds Postal spent processing the request
+    pub time: f64,
+    /// Flags of the response
+    pub flags: ResponseFlags,
+    /// The size of the request body in bytes
+    #[serde(default)]
+    pub bytes_sent: usize,
+    /// The size of the response body in bytes
+    #[serde(default)]
+    pub bytes_received: usize,
+    /// Rate limit headers of the response if any
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitInfo>,
+}
+
+/// RateLimitInfo holds a rate limit quota reported by response headers
+///
+/// Postal itself doesn't limit requests,
+/// the headers usually come from a gateway in front of it.
+/// Names of the headers are set by [ClientBuilder::rate_limit_headers],
+/// except the standard `Retry-After` one.
+///
+/// [ClientBuilder::rate_limit_headers]: ./struct.ClientBuilder.html#method.rate_limit_headers
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    /// How many requests are allowed in a window
+    pub limit: Option<u64>,
+    /// How many requests are left in the current window
+    pub remaining: Option<u64>,
+    /// When the window resets, usually in seconds
+    pub reset: Option<u64>,
+    /// How many seconds to wait before sending a request again
+    ///
+    /// It's read from the `Retry-After` header,
+    /// a date in it is converted to seconds from now.
+    #[serde(default)]
+    pub retry_after: Option<u64>,
+}
+
+/// RateLimitHeaders holds names of headers a rate limit is reported in
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RateLimitHeaders {
+    /// The header with the limit
+    pub limit: String,
+    /// The header with the number of requests left
+    pub remaining: String,
+    /// The header with the time the limit resets
+    pub reset: String,
+}
+
+impl Default for RateLimitHeaders {
+    /// The `X-RateLimit-*` headers
+    fn default() -> Self {
+        Self {
+            limit: "X-RateLimit-Limit".to_owned(),
+            remaining: "X-RateLimit-Remaining".to_owned(),
+            reset: "X-RateLimit-Reset".to_owned(),
+        }
+    }
+}
+
+impl RateLimitHeaders {
+    /// Reads a rate limit from headers
+    ///
+    /// It returns `None` if none of the headers is present.
+    pub(crate) fn parse(&self, headers: &HeaderMap) -> Option<RateLimitInfo> {
+        let value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+
+        let info = RateLimitInfo {
+            limit: value(&self.limit),
+            remaining: value(&self.remaining),
+            reset: value(&self.reset),
+            retry_after: retry_after(headers),
+        };
+        if info == RateLimitInfo::default() {
+            None
+        } else {
+            Some(info)
+        }
+    }
+}
+
+/// Reads the `Retry-After` header which holds either seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    let seconds = date
+        .duration_since(SystemTime::now())
+        .map(|delay| delay.as_secs())
+        .unwrap_or(0);
+    Some(seconds)
+}
+
+/// ResponseFlags holds flags of a Postal response
+///
+/// Flags which are not known are kept in `other`.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::ResponseFlags;
+///
+/// let flags: ResponseFlags =
+///     serde_json::from_str(r#"{"ignore_error_details": 1, "new_flag": 2}"#).unwrap();
+///
+/// assert_eq!(flags.ignore_error_details, Some(1));
+/// assert_eq!(flags.other["new_flag"], 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFlags {
+    /// Set when Postal hides details of an error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_error_details: Option<u64>,
+    /// The raw flags which are not known
+    #[serde(flatten)]
+    pub other: HashMap<String, Json>,
+}
@@ -0,0 +1,567 @@
+use crate::{address, Message, MessageAttachment, PostalError};
+use encoding_rs::Encoding;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The charset bodies are encoded with if none is set
+pub(crate) const DEFAULT_CHARSET: &str = "utf-8";
+
+/// Builds an RFC2822 message
+///
+/// Headers are built from the addresses and the subject of the message,
+/// additional headers of the message are written after them.
+/// `bcc` recipients are not mentioned in the headers.
+pub(crate) fn build(message: &Message) -> Result<Vec<u8>, PostalError> {
+    let charset = message.charset.as_deref().unwrap_or(DEFAULT_CHARSET);
+    let encoding = Encoding::for_label(charset.as_bytes())
+        .ok_or_else(|| PostalError::InvalidMessage(format!("unknown charset {:?}", charset)))?;
+
+    let mut out = Vec::new();
+    if let Some(from) = &message.from {
+        header(&mut out, "From", from)?;
+    }
+    if let Some(sender) = &message.sender {
+        header(&mut out, "Sender", sender)?;
+    }
+    if let Some(to) = &message.to {
+        address_list(&mut out, "To", to)?;
+    }
+    if let Some(cc) = &message.cc {
+        address_list(&mut out, "Cc", cc)?;
+    }
+    if let Some(reply_to) = &message.reply_to {
+        header(&mut out, "Reply-To", reply_to)?;
+    }
+    if let Some(subject) = &message.subject {
+        header(&mut out, "Subject", &encode_word(subject))?;
+    }
+    if let Some(headers) = &message.headers {
+        let mut headers: Vec<_> = headers
+            .iter()
+            .filter(|(name, _)| !BUILT_HEADERS.iter().any(|h| name.eq_ignore_ascii_case(h)))
+            .collect();
+        headers.sort();
+        for (name, value) in headers {
+            header(&mut out, name, &encode_word(value))?;
+        }
+    }
+    header(&mut out, "MIME-Version", "1.0")?;
+
+    let attachments = message.attachments.as_deref().unwrap_or_default();
+    if attachments.is_empty() {
+        body_part(&mut out, message, encoding)?;
+    } else {
+        let boundary = boundary();
+        header(
+            &mut out,
+            "Content-Type",
+            &format!("multipart/mixed; boundary=\"{}\"", boundary),
+        )?;
+        out.extend_from_slice(b"\r\n");
+        out.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        body_part(&mut out, message, encoding)?;
+        for attachment in attachments {
+            out.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            attachment_part(&mut out, attachment)?;
+        }
+        out.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    }
+
+    Ok(out)
+}
+
+/// Headers built by [build] itself, additional headers
+/// of a message with these names are not written
+const BUILT_HEADERS: &[&str] = &[
+    "From",
+    "Sender",
+    "To",
+    "Cc",
+    "Bcc",
+    "Reply-To",
+    "Subject",
+    "MIME-Version",
+    "Content-Type",
+    "Content-Transfer-Encoding",
+];
+
+/// The maximum length of a line RFC 5322 allows, without CRLF
+const MAX_LINE_LENGTH: usize = 998;
+
+/// Writes the bodies of a message
+///
+/// When both a plain and an HTML bodies are set
+/// they are put into a `multipart/alternative` part.
+fn body_part(
+    out: &mut Vec<u8>,
+    message: &Message,
+    encoding: &'static Encoding,
+) -> Result<(), PostalError> {
+    let plain = message
+        .plain_body
+        .as_deref()
+        .map(|body| ("text/plain", body));
+    let html = message.html_body.as_deref().map(|body| ("text/html", body));
+    match (plain, html) {
+        (Some(plain), Some(html)) => {
+            let boundary = boundary();
+            header(
+                out,
+                "Content-Type",
+                &format!("multipart/alternative; boundary=\"{}\"", boundary),
+            )?;
+            out.extend_from_slice(b"\r\n");
+            for (content_type, body) in [plain, html].iter() {
+                out.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+                text_part(out, content_type, body, encoding)?;
+            }
+            out.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+            Ok(())
+        }
+        (Some((content_type, body)), None) | (None, Some((content_type, body))) => {
+            text_part(out, content_type, body, encoding)
+        }
+        (None, None) => text_part(out, "text/plain", "", encoding),
+    }
+}
+
+/// Writes an attachment
+///
+/// An inline attachment gets `Content-Disposition: inline`
+/// and its `Content-ID`.
+fn attachment_part(out: &mut Vec<u8>, attachment: &MessageAttachment) -> Result<(), PostalError> {
+    let name = quote(&encode_word(attachment.name()));
+    header(
+        out,
+        "Content-Type",
+        &format!("{}; name={}", attachment.content_type(), name),
+    )?;
+    header(out, "Content-Transfer-Encoding", "base64")?;
+    match attachment.content_id() {
+        Some(content_id) => {
+            header(
+                out,
+                "Content-Disposition",
+                &format!("inline; filename={}", name),
+            )?;
+            header(out, "Content-ID", &format!("<{}>", content_id))?;
+        }
+        None => header(
+            out,
+            "Content-Disposition",
+            &format!("attachment; filename={}", name),
+        )?,
+    }
+    out.extend_from_slice(b"\r\n");
+    match attachment {
+        MessageAttachment::Plain(attachment) => base64_lines(out, &attachment.data),
+        MessageAttachment::Encoded(attachment) => encoded_lines(out, attachment.encoded()),
+    }
+    Ok(())
+}
+
+/// Quotes a header parameter value
+fn quote(value: &str) -> String {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", value)
+}
+
+/// Parses headers of an RFC2822 message
+///
+/// Folded headers are unfolded, values are not decoded.
+pub(crate) fn parse_headers(message: &[u8]) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in message.split(|&b| b == b'\n') {
+        let line = String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(line));
+        if line.is_empty() {
+            break;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+
+        if let Some(colon) = line.find(':') {
+            let name = line[..colon].trim().to_owned();
+            let value = line[colon + 1..].trim().to_owned();
+            headers.push((name, value));
+        }
+    }
+
+    headers
+}
+
+/// Returns addresses from `to`, `cc` and `bcc` without display names
+pub(crate) fn recipients(message: &Message) -> Vec<String> {
+    message
+        .recipients()
+        .map(|to| address::mailbox(to).to_owned())
+        .collect()
+}
+
+/// Writes a text part, the body is encoded by the charset.
+///
+/// ASCII only bodies with lines no longer than RFC 5322 allows
+/// are written as is, the others are encoded by base64.
+/// It fails if a character can't be encoded by the charset.
+fn text_part(
+    out: &mut Vec<u8>,
+    content_type: &str,
+    body: &str,
+    encoding: &'static Encoding,
+) -> Result<(), PostalError> {
+    let (body, encoding, had_errors) = encoding.encode(body);
+    if had_errors {
+        return Err(PostalError::InvalidMessage(format!(
+            "a body can't be encoded in {}",
+            encoding.name()
+        )));
+    }
+
+    let content_type = format!(
+        "{}; charset={}",
+        content_type,
+        encoding.name().to_lowercase()
+    );
+    header(out, "Content-Type", &content_type)?;
+
+    let lines = || {
+        body.split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+    };
+    if body.is_ascii() && lines().all(|line| line.len() <= MAX_LINE_LENGTH) {
+        header(out, "Content-Transfer-Encoding", "7bit")?;
+        out.extend_from_slice(b"\r\n");
+        for line in lines() {
+            out.extend_from_slice(line);
+            out.extend_from_slice(b"\r\n");
+        }
+    } else {
+        header(out, "Content-Transfer-Encoding", "base64")?;
+        out.extend_from_slice(b"\r\n");
+        base64_lines(out, &body);
+    }
+
+    Ok(())
+}
+
+/// Writes base64 encoded data split into lines of 76 characters
+pub(crate) fn base64_lines(out: &mut Vec<u8>, data: &[u8]) {
+    encoded_lines(out, &base64::encode(data));
+}
+
+/// Writes an already encoded content in lines of 76 characters
+fn encoded_lines(out: &mut Vec<u8>, encoded: &str) {
+    for line in encoded.as_bytes().chunks(76) {
+        out.extend_from_slice(line);
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+/// Writes a header
+///
+/// It fails if the name is not valid or the value has a line break,
+/// which would let the value start another header.
+pub(crate) fn header(out: &mut Vec<u8>, name: &str, value: &str) -> Result<(), PostalError> {
+    check_header(name, value)?;
+    write_header(out, name, value);
+    Ok(())
+}
+
+/// Writes a header with a list of addresses
+///
+/// Each address is put on its own line,
+/// so a long list doesn't exceed the limit of a line.
+fn address_list(out: &mut Vec<u8>, name: &str, list: &[String]) -> Result<(), PostalError> {
+    if list.is_empty() {
+        return Ok(());
+    }
+
+    for address in list {
+        check_header(name, address)?;
+    }
+    let value: Vec<&str> = list.iter().map(|address| address.trim()).collect();
+    write_header(out, name, &value.join(",\r\n "));
+    Ok(())
+}
+
+fn check_header(name: &str, value: &str) -> Result<(), PostalError> {
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_graphic() && b != b':') {
+        return Err(PostalError::InvalidMessage(format!(
+            "invalid header name {:?}",
+            name
+        )));
+    }
+    if value.contains(['\r', '\n']) {
+        return Err(PostalError::InvalidMessage(format!(
+            "a value of the {} header contains a line break",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
+fn write_header(out: &mut Vec<u8>, name: &str, value: &str) {
+    out.extend_from_slice(name.as_bytes());
+    out.extend_from_slice(b": ");
+    out.extend_from_slice(value.as_bytes());
+    out.extend_from_slice(b"\r\n");
+}
+
+/// Encodes a non ASCII header value as an RFC2047 encoded word
+pub(crate) fn encode_word(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_owned()
+    } else {
+        format!("=?utf-8?B?{}?=", base64::encode(value))
+    }
+}
+
+pub(crate) fn boundary() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos())
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    format!("=_postal_{:x}_{:x}", nanos, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Attachment, EncodedAttachment};
+
+    fn message() -> Message {
+        Message::default()
+            .to(&["example@gmail.com".to_owned()])
+            .from("test@yourserver.io")
+    }
+
+    fn build_str(message: &Message) -> String {
+        String::from_utf8(build(message).unwrap()).unwrap()
+    }
+
+    fn header_values<'a>(data: &'a str, name: &str) -> Vec<&'a str> {
+        let prefix = format!("{}: ", name);
+        data.split("\r\n")
+            .filter_map(|line| line.strip_prefix(prefix.as_str()))
+            .collect()
+    }
+
+    /// Returns the body of a message with a single text part
+    fn decoded_body(data: &str) -> Vec<u8> {
+        let (headers, body) = data.split_at(data.find("\r\n\r\n").unwrap());
+        if headers.contains("Content-Transfer-Encoding: base64") {
+            let body: String = body.split_whitespace().collect();
+            base64::decode(body).unwrap()
+        } else {
+            body.trim_start().as_bytes().to_vec()
+        }
+    }
+
+    #[test]
+    fn emoji_survives_a_round_trip() {
+        let text = "Hello 😀, привет!";
+        let raw = message()
+            .subject(text)
+            .text(text)
+            .into_raw_message()
+            .unwrap();
+        let data = String::from_utf8(base64::decode(&raw.data).unwrap()).unwrap();
+
+        assert_eq!(
+            header_values(&data, "Content-Type"),
+            ["text/plain; charset=utf-8"]
+        );
+        assert_eq!(
+            header_values(&data, "Subject"),
+            [format!("=?utf-8?B?{}?=", base64::encode(text))]
+        );
+        assert_eq!(String::from_utf8(decoded_body(&data)).unwrap(), text);
+    }
+
+    #[test]
+    fn body_in_a_charset() {
+        let data = build_str(&message().charset("iso-8859-1").text("héllo"));
+
+        assert_eq!(
+            header_values(&data, "Content-Type"),
+            ["text/plain; charset=windows-1252"]
+        );
+        assert_eq!(decoded_body(&data), b"h\xe9llo");
+    }
+
+    #[test]
+    fn unmappable_characters_are_refused() {
+        let err = build(&message().charset("iso-8859-1").text("héllo 😀")).unwrap_err();
+
+        assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn utf16_is_written_as_utf8() {
+        let data = build_str(&message().charset("utf-16").text("héllo"));
+
+        assert_eq!(
+            header_values(&data, "Content-Type"),
+            ["text/plain; charset=utf-8"]
+        );
+        assert_eq!(decoded_body(&data), "héllo".as_bytes());
+    }
+
+    #[test]
+    fn charset_is_not_serialized() {
+        let json = message().charset("iso-8859-1").to_json().unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+
+        assert!(json.get("charset").is_none());
+    }
+
+    #[test]
+    fn unknown_charset() {
+        let err = build(&message().charset("no-such-charset").text("hi")).unwrap_err();
+
+        assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn ascii_body_is_7bit() {
+        let data = build_str(&message().text("line 1\nline 2"));
+
+        assert_eq!(header_values(&data, "Content-Transfer-Encoding"), ["7bit"]);
+        assert!(data.ends_with("\r\n\r\nline 1\r\nline 2\r\n"));
+    }
+
+    #[test]
+    fn long_lines_are_encoded() {
+        let html = format!("<p>{}</p>", "a".repeat(2000));
+        let data = build_str(&message().html(html.as_str()));
+
+        assert_eq!(
+            header_values(&data, "Content-Transfer-Encoding"),
+            ["base64"]
+        );
+        assert!(data.split("\r\n").all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(decoded_body(&data), html.as_bytes());
+    }
+
+    #[test]
+    fn line_breaks_in_headers_are_refused() {
+        let messages = vec![
+            message().subject("Hi\r\nBcc: evil@x.io"),
+            message().subject("Hi\nBcc: evil@x.io"),
+            message().from_with_name("Evil\r\nBcc: evil@x.io", "evil@x.io"),
+            message().sender("a@x.io\r\nBcc: evil@x.io"),
+            message().to(&["a@x.io\r\nBcc: evil@x.io".to_owned()]),
+            message().header("X-Custom", "value\r\nBcc: evil@x.io"),
+            message().header("X-Custom\r\nBcc", "evil@x.io"),
+            message().header("X-Custom: value", "evil@x.io"),
+            message().attachment(Attachment::new(
+                "a.txt\r\nBcc: evil@x.io",
+                "text/plain",
+                "a",
+            )),
+        ];
+
+        for message in messages {
+            let err = build(&message).unwrap_err();
+            assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn additional_headers_are_written() {
+        let message = message()
+            .subject("Hi")
+            .message_id("1234@example.com")
+            .header("X-Custom", "value")
+            .header("X-Name", "Jörg")
+            .header("subject", "ignored")
+            .header("Content-Type", "ignored");
+        let data = build_str(&message);
+
+        assert_eq!(header_values(&data, "Message-ID"), ["<1234@example.com>"]);
+        assert_eq!(header_values(&data, "X-Custom"), ["value"]);
+        assert_eq!(header_values(&data, "X-Name"), ["=?utf-8?B?SsO2cmc=?="]);
+        assert_eq!(header_values(&data, "Subject"), ["Hi"]);
+        assert_eq!(header_values(&data, "subject"), Vec::<&str>::new());
+        assert_eq!(
+            header_values(&data, "Content-Type"),
+            ["text/plain; charset=utf-8"]
+        );
+    }
+
+    #[test]
+    fn long_address_lists_are_folded() {
+        let to: Vec<String> = (0..50)
+            .map(|i| format!("recipient{}@example.com", i))
+            .collect();
+        let mut message = message().to(&to).text("Hi");
+        message.cc = Some(to.clone());
+        let data = build_str(&message);
+
+        let head = &data[..data.find("\r\n\r\n").unwrap()];
+        assert!(head.split("\r\n").all(|line| line.len() <= 78));
+
+        let headers = parse_headers(data.as_bytes());
+        let addresses = |name| {
+            let (_, value) = headers.iter().find(|(key, _)| key == name).unwrap();
+            crate::address::split_list(value)
+                .into_iter()
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(addresses("To"), to);
+        assert_eq!(addresses("Cc"), to);
+    }
+
+    #[test]
+    fn encoded_attachment() {
+        let attachment = Attachment::new("a.bin", "application/octet-stream", vec![7; 200]);
+        let part = |message: Message| {
+            let data = build_str(&message.text("Hi"));
+            let start = data.find("Content-Type: application/octet-stream").unwrap();
+            let end = data.rfind("--").unwrap();
+            let part = data[start..end].to_owned();
+            part[..part.rfind("--").unwrap()].to_owned()
+        };
+
+        assert_eq!(
+            part(message().attachment(EncodedAttachment::new(attachment.clone()))),
+            part(message().attachment(attachment))
+        );
+    }
+
+    #[test]
+    fn inline_attachment() {
+        let data = build_str(
+            &message()
+                .html(r#"<img src="cid:logo">"#)
+                .attachment(Attachment::inline(
+                    "logo.png",
+                    "image/png",
+                    vec![1, 2, 3],
+                    "<logo>",
+                ))
+                .attachment(Attachment::new("a.txt", "text/plain", "text")),
+        );
+
+        assert_eq!(header_values(&data, "Content-ID"), ["<logo>"]);
+        assert_eq!(
+            header_values(&data, "Content-Disposition"),
+            [
+                "inline; filename=\"logo.png\"",
+                "attachment; filename=\"a.txt\"",
+            ]
+        );
+        assert!(data.contains("Content-Type: image/png; name=\"logo.png\""));
+    }
+}
@@ -0,0 +1,39 @@
+/// Priority of a message
+///
+/// Mail clients read a priority from different headers,
+/// so it's set by `X-Priority`, `Importance` and `Priority` at once,
+/// see [Message::priority].
+///
+/// [Message::priority]: ./struct.Message.html#method.priority
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Priority {
+    /// An urgent message e.g. a password reset
+    High,
+    /// A usual message
+    Normal,
+    /// A message which is not urgent e.g. a newsletter
+    Low,
+}
+
+impl Priority {
+    /// Returns the headers which mark a message with the priority
+    pub fn headers(&self) -> [(&'static str, &'static str); 3] {
+        match self {
+            Self::High => [
+                ("X-Priority", "1 (Highest)"),
+                ("Importance", "high"),
+                ("Priority", "urgent"),
+            ],
+            Self::Normal => [
+                ("X-Priority", "3 (Normal)"),
+                ("Importance", "normal"),
+                ("Priority", "normal"),
+            ],
+            Self::Low => [
+                ("X-Priority", "5 (Lowest)"),
+                ("Importance", "low"),
+                ("Priority", "non-urgent"),
+            ],
+        }
+    }
+}
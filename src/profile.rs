@@ -0,0 +1,82 @@
+use crate::{Message, PostalError};
+use serde_json::Value as Json;
+
+/// WireProfile controls names of keys a [Message] is serialized with
+///
+/// Some forks of Postal name fields of a message differently,
+/// a profile allows the crate to talk to them.
+/// A profile is chosen by [ClientBuilder::wire_profile].
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::{Message, WireProfile};
+///
+/// let message = Message::default().text("Hi").html("<p>Hi</p>");
+///
+/// let json = WireProfile::Postal.serialize(&message).unwrap();
+/// assert_eq!(json["plain_body"], "Hi");
+/// assert_eq!(json["html_body"], "<p>Hi</p>");
+///
+/// let json = WireProfile::TextHtml.serialize(&message).unwrap();
+/// assert_eq!(json["text"], "Hi");
+/// assert_eq!(json["html"], "<p>Hi</p>");
+/// assert!(json.get("plain_body").is_none());
+/// ```
+///
+/// [Message]: ./struct.Message.html
+/// [ClientBuilder::wire_profile]: ./struct.ClientBuilder.html#method.wire_profile
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum WireProfile {
+    /// Keys used by Postal
+    #[default]
+    Postal,
+    /// Keys used by forks which name bodies `text` and `html`
+    TextHtml,
+}
+
+impl WireProfile {
+    /// Returns a key a field of [Message] is sent with
+    ///
+    /// [Message]: ./struct.Message.html
+    pub fn key<'a>(&self, field: &'a str) -> &'a str {
+        match (self, field) {
+            (Self::TextHtml, "plain_body") => "text",
+            (Self::TextHtml, "html_body") => "html",
+            _ => field,
+        }
+    }
+
+    /// Serializes a message into a JSON body with keys of the profile
+    ///
+    /// It fails with [PostalError::InvalidMessage] if an extra field
+    /// of the message has a key of a modeled one.
+    ///
+    /// [PostalError::InvalidMessage]: ./enum.PostalError.html#variant.InvalidMessage
+    pub fn serialize(&self, message: &Message) -> Result<Json, PostalError> {
+        message.check_extra(*self)?;
+        let json = serde_json::to_value(message)?;
+        if *self == Self::Postal {
+            return Ok(json);
+        }
+
+        let json = match json {
+            Json::Object(map) => map
+                .into_iter()
+                .map(|(field, value)| (self.key(&field).to_owned(), value))
+                .collect(),
+            json => json,
+        };
+
+        Ok(json)
+    }
+
+    /// Sets fields of a message which are not in a JSON body to `null`
+    pub(crate) fn fill_nulls(&self, json: &mut Json) {
+        if let Json::Object(map) = json {
+            for field in Message::FIELDS {
+                map.entry(self.key(field)).or_insert(Json::Null);
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+/// ContextPropagator provides headers which carry a trace context
+/// e.g. W3C `traceparent` and `tracestate`
+///
+/// It's called on each request within the context the request is sent in,
+/// so the headers can be derived from the active span.
+///
+/// It's implemented for closures.
+/// With the `otel` feature [OtelPropagator] is used by default.
+///
+/// # Examples
+///
+/// A propagator of OpenTelemetry can be used as
+///
+/// ```ignore
+/// use opentelemetry::global;
+/// use postal_rs::Client;
+/// use std::collections::HashMap;
+///
+/// let client = Client::builder("https://postal.example.com", "token")
+///     .context_propagator(|| {
+///         let mut headers = HashMap::new();
+///         global::get_text_map_propagator(|propagator| propagator.inject(&mut headers));
+///         headers.into_iter().collect()
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// [OtelPropagator]: ./struct.OtelPropagator.html
+pub trait ContextPropagator: Send + Sync {
+    /// Returns headers which are added to a request
+    fn headers(&self) -> Vec<(String, String)>;
+}
+
+impl<F> ContextPropagator for F
+where
+    F: Fn() -> Vec<(String, String)> + Send + Sync,
+{
+    fn headers(&self) -> Vec<(String, String)> {
+        self()
+    }
+}
+
+/// The name of a header a W3C trace context is sent in
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// The name of a header vendor specific W3C trace data is sent in
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Formats a value of a W3C `traceparent` header
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::traceparent;
+///
+/// assert_eq!(
+///     traceparent(0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7, true),
+///     "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+/// );
+/// ```
+pub fn traceparent(trace_id: u128, span_id: u64, sampled: bool) -> String {
+    format!(
+        "00-{:032x}-{:016x}-{:02x}",
+        trace_id, span_id, sampled as u8
+    )
+}
+
+/// OtelPropagator adds W3C `traceparent` and `tracestate` headers
+/// of the span which is active in the current OpenTelemetry context
+///
+/// Nothing is added if there's no valid span.
+/// The globally installed text map propagator isn't used,
+/// so the headers are sent even if an application hasn't set one.
+#[cfg(feature = "otel")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OtelPropagator;
+
+#[cfg(feature = "otel")]
+impl ContextPropagator for OtelPropagator {
+    fn headers(&self) -> Vec<(String, String)> {
+        use opentelemetry::trace::TraceContextExt;
+
+        let context = opentelemetry::Context::current();
+        let span = context.span();
+        let span_context = span.span_context();
+        if !span_context.is_valid() {
+            return Vec::new();
+        }
+
+        let parent = traceparent(
+            u128::from_be_bytes(span_context.trace_id().to_bytes()),
+            u64::from_be_bytes(span_context.span_id().to_bytes()),
+            span_context.is_sampled(),
+        );
+        let mut headers = vec![(TRACEPARENT_HEADER.to_owned(), parent)];
+
+        let state = span_context.trace_state().header();
+        if !state.is_empty() {
+            headers.push((TRACESTATE_HEADER.to_owned(), state));
+        }
+
+        headers
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{Message, PostalError};
+use std::collections::HashMap;
+
+/// Template renders messages with `{{name}}` placeholders
+///
+/// A placeholder is replaced by a value of a variable with the same name,
+/// spaces around the name are ignored.
+/// Values are escaped in the HTML body but not in the subject and the plain body.
+/// There's no control flow, a template only substitutes variables.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::Template;
+/// use std::collections::HashMap;
+///
+/// let template = Template::default()
+///     .subject("Welcome, {{name}}")
+///     .text("Hi {{ name }}, your code is {{code}}")
+///     .html("<p>Hi <b>{{name}}</b></p>");
+///
+/// let mut vars = HashMap::new();
+/// vars.insert("name".to_owned(), "Tom & <Jerry>".to_owned());
+/// vars.insert("code".to_owned(), "1234".to_owned());
+///
+/// let message = template
+///     .render(&vars)
+///     .unwrap()
+///     .to(&["tom@example.com".to_owned()])
+///     .from("test@yourserver.io");
+///
+/// assert_eq!(message.subject.as_deref(), Some("Welcome, Tom & <Jerry>"));
+/// assert_eq!(message.plain_body.as_deref(), Some("Hi Tom & <Jerry>, your code is 1234"));
+/// assert_eq!(
+///     message.html_body.as_deref(),
+///     Some("<p>Hi <b>Tom &amp; &lt;Jerry&gt;</b></p>")
+/// );
+///
+/// vars.remove("code");
+/// assert!(template.render(&vars).is_err());
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Template {
+    subject: Option<String>,
+    plain_body: Option<String>,
+    html_body: Option<String>,
+}
+
+impl Template {
+    /// Sets a template of the subject
+    pub fn subject<S: Into<String>>(mut self, template: S) -> Self {
+        self.subject = Some(template.into());
+        self
+    }
+
+    /// Sets a template of the plain text body
+    pub fn text<S: Into<String>>(mut self, template: S) -> Self {
+        self.plain_body = Some(template.into());
+        self
+    }
+
+    /// Sets a template of the HTML body
+    pub fn html<S: Into<String>>(mut self, template: S) -> Self {
+        self.html_body = Some(template.into());
+        self
+    }
+
+    /// Renders a message with the given variables
+    ///
+    /// It fails with [PostalError::InvalidMessage]
+    /// if a placeholder has no variable.
+    ///
+    /// [PostalError::InvalidMessage]: ./enum.PostalError.html#variant.InvalidMessage
+    pub fn render(&self, vars: &HashMap<String, String>) -> Result<Message, PostalError> {
+        let render = |template: &Option<String>, escape: fn(&str) -> String| {
+            template
+                .as_deref()
+                .map(|template| substitute(template, vars, escape))
+                .transpose()
+        };
+
+        Ok(Message {
+            subject: render(&self.subject, str::to_owned)?,
+            plain_body: render(&self.plain_body, str::to_owned)?,
+            html_body: render(&self.html_body, escape_html)?,
+            ..Message::default()
+        })
+    }
+}
+
+fn substitute(
+    template: &str,
+    vars: &HashMap<String, String>,
+    escape: fn(&str) -> String,
+) -> Result<String, PostalError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start + 2..].find("}}") {
+            Some(end) => start + 2 + end,
+            None => break,
+        };
+
+        let name = rest[start + 2..end].trim();
+        let value = vars.get(name).ok_or_else(|| {
+            PostalError::InvalidMessage(format!("no value of a template variable {:?}", name))
+        })?;
+
+        out.push_str(&rest[..start]);
+        out.push_str(&escape(value));
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
@@ -0,0 +1,324 @@
+//! Helpers for testing code which uses the crate.
+//!
+//! The module is available with the `testing` feature.
+
+use crate::Client;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use serde_json::Value as Json;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// The API token of a client returned by [MockPostal::client]
+///
+/// [MockPostal::client]: ./struct.MockPostal.html#method.client
+pub const MOCK_TOKEN: &str = "mock-token";
+
+/// MockPostal is a local HTTP server which imitates Postal
+///
+/// By default it accepts all messages, returning an id for each recipient,
+/// returns an empty details for any message and no deliveries.
+/// Other paths get a `404 Not Found` status.
+/// A response for an endpoint can be overridden by [respond].
+///
+/// The server is stopped when the mock is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::testing::MockPostal;
+/// use postal_rs::Message;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mock = MockPostal::start().await;
+///     let message = Message::default()
+///         .to(&["example@gmail.com".to_owned()])
+///         .from("test@yourserver.io")
+///         .text("A test message");
+///
+///     let results = mock.client().send(message).await.unwrap();
+///
+///     assert_eq!(results[0].to, "example@gmail.com");
+///     assert_eq!(mock.requests()[0].path, "/api/v1/send/message");
+/// }
+/// ```
+///
+/// [respond]: #method.respond
+#[derive(Debug)]
+pub struct MockPostal {
+    address: SocketAddr,
+    state: Arc<State>,
+    _shutdown: oneshot::Sender<()>,
+}
+
+/// MockRequest is a request received by [MockPostal]
+///
+/// [MockPostal]: ./struct.MockPostal.html
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// The path of the request
+    pub path: String,
+    /// The address of the client connection the request came over
+    pub peer: SocketAddr,
+    /// Headers of the request with lowercase names
+    pub headers: Vec<(String, String)>,
+    /// The body of the request
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// Returns a value of a header
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parses the body as JSON
+    pub fn json(&self) -> Option<Json> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    responses: Mutex<HashMap<String, VecDeque<(u16, String)>>>,
+    headers: Mutex<HashMap<String, Vec<(String, String)>>>,
+    requests: Mutex<Vec<MockRequest>>,
+    next_id: AtomicU64,
+}
+
+impl MockPostal {
+    /// Starts a server on a random local port
+    ///
+    /// # Panics
+    ///
+    /// It panics if it's not called within a tokio runtime
+    /// or if a port can't be bound.
+    pub async fn start() -> Self {
+        let state = Arc::new(State::default());
+        let (shutdown, stopped) = oneshot::channel::<()>();
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |conn: &AddrStream| {
+            let state = service_state.clone();
+            let peer = conn.remote_addr();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), peer, req))) }
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let address = server.local_addr();
+        let server = server.with_graceful_shutdown(async {
+            let _ = stopped.await;
+        });
+        tokio::spawn(server);
+
+        Self {
+            address,
+            state,
+            _shutdown: shutdown,
+        }
+    }
+
+    /// Returns the URL of the server
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+
+    /// Returns a client pointed at the server
+    pub fn client(&self) -> Client {
+        Client::new(self.url(), MOCK_TOKEN).expect("a mock URL is always valid")
+    }
+
+    /// Adds a response for a path e.g. `/api/v1/send/message`
+    ///
+    /// Responses are returned in the order they were added,
+    /// the last one is repeated for all following requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use postal_rs::testing::MockPostal;
+    /// use postal_rs::{Message, PostalError};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mock = MockPostal::start().await;
+    ///     mock.respond("/api/v1/send/message", 200, "<html>Maintenance</html>");
+    ///     let message = Message::default()
+    ///         .to(&["example@gmail.com".to_owned()])
+    ///         .from("test@yourserver.io")
+    ///         .text("A test message");
+    ///
+    ///     let err = mock.client().send(message).await.unwrap_err();
+    ///
+    ///     assert!(matches!(
+    ///         err,
+    ///         PostalError::Decode { body, .. } if body == "<html>Maintenance</html>"
+    ///     ));
+    /// }
+    /// ```
+    pub fn respond<S: Into<String>>(&self, path: &str, status: u16, body: S) {
+        self.state
+            .responses
+            .lock()
+            .unwrap()
+            .entry(path.to_owned())
+            .or_default()
+            .push_back((status, body.into()));
+    }
+
+    /// Adds a successful response with the given data for a path
+    pub fn respond_success(&self, path: &str, data: Json) {
+        let body = serde_json::json!({
+            "status": "success",
+            "time": 0.01,
+            "flags": {},
+            "data": data,
+        });
+        self.respond(path, 200, body.to_string());
+    }
+
+    /// Adds an error response with the given code for a path
+    pub fn respond_error(&self, path: &str, code: &str, message: &str) {
+        let body = serde_json::json!({
+            "status": "error",
+            "time": 0.01,
+            "flags": {},
+            "data": { "code": code, "message": message },
+        });
+        self.respond(path, 200, body.to_string());
+    }
+
+    /// Adds headers to all responses for a path
+    pub fn respond_with_headers(&self, path: &str, headers: &[(&str, &str)]) {
+        self.state
+            .headers
+            .lock()
+            .unwrap()
+            .entry(path.to_owned())
+            .or_default()
+            .extend(
+                headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string())),
+            );
+    }
+
+    /// Returns all requests received by the server
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+impl State {
+    fn response(&self, path: &str, body: &[u8]) -> (u16, String) {
+        let mut responses = self.responses.lock().unwrap();
+        if let Some(queue) = responses.get_mut(path) {
+            let response = if queue.len() > 1 {
+                queue.pop_front()
+            } else {
+                queue.front().cloned()
+            };
+
+            if let Some(response) = response {
+                return response;
+            }
+        }
+
+        let endpoint = path.find("api/v1/").map_or(path, |i| &path[i..]);
+        let data = match endpoint {
+            "api/v1/send/message" | "api/v1/send/raw" => self.accept(body),
+            "api/v1/messages/message" => {
+                let id = serde_json::from_slice::<Json>(body)
+                    .ok()
+                    .and_then(|body| body["id"].as_u64())
+                    .unwrap_or_default();
+                serde_json::json!({ "id": id, "token": "mock" })
+            }
+            "api/v1/messages/deliveries" => serde_json::json!([]),
+            _ => return (404, "Not Found".to_owned()),
+        };
+
+        let body = serde_json::json!({
+            "status": "success",
+            "time": 0.01,
+            "flags": {},
+            "data": data,
+        });
+        (200, body.to_string())
+    }
+
+    fn accept(&self, body: &[u8]) -> Json {
+        let body: Json = serde_json::from_slice(body).unwrap_or_default();
+        let messages: serde_json::Map<String, Json> = ["to", "cc", "bcc", "rcpt_to"]
+            .iter()
+            .filter_map(|field| body[*field].as_array())
+            .flatten()
+            .filter_map(Json::as_str)
+            .map(|to| {
+                let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+                let token = format!("mock-{}", id);
+                (
+                    to.to_owned(),
+                    serde_json::json!({ "id": id, "token": token }),
+                )
+            })
+            .collect();
+
+        serde_json::json!({ "message_id": "mock@postal", "messages": messages })
+    }
+}
+
+async fn handle(
+    state: Arc<State>,
+    peer: SocketAddr,
+    req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let path = req.uri().path().to_owned();
+    let headers = req
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned()))
+        })
+        .collect();
+    let body = hyper::body::to_bytes(req.into_body())
+        .await
+        .map(|body| body.to_vec())
+        .unwrap_or_default();
+
+    let (status, response) = state.response(&path, &body);
+    let response_headers = state
+        .headers
+        .lock()
+        .unwrap()
+        .get(&path)
+        .cloned()
+        .unwrap_or_default();
+    state.requests.lock().unwrap().push(MockRequest {
+        path,
+        peer,
+        headers,
+        body,
+    });
+
+    let mut builder = Response::builder()
+        .status(status)
+        .header("content-type", "application/json");
+    for (name, value) in response_headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let response = builder
+        .body(Body::from(response))
+        .expect("a response is always valid");
+
+    Ok(response)
+}
@@ -0,0 +1,45 @@
+/// TokenProvider provides an API token for each request
+///
+/// It allows a single [Client] and its connection pool to be used
+/// on behalf of several Postal servers, e.g. one per tenant.
+///
+/// It's implemented for closures.
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::{Client, TokenContext};
+///
+/// let client = Client::builder("https://postal.example.com", "default-token")
+///     .token_provider(|context: &TokenContext<'_>| match context.from {
+///         Some(from) if from.ends_with("@tenant.io") => "tenant-token".to_owned(),
+///         _ => "default-token".to_owned(),
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// [Client]: ./struct.Client.html
+pub trait TokenProvider: Send + Sync {
+    /// Returns a token for a request
+    fn token_for(&self, context: &TokenContext<'_>) -> String;
+}
+
+impl<F> TokenProvider for F
+where
+    F: Fn(&TokenContext<'_>) -> String + Send + Sync,
+{
+    fn token_for(&self, context: &TokenContext<'_>) -> String {
+        self(context)
+    }
+}
+
+/// TokenContext describes a request a token is requested for
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TokenContext<'a> {
+    /// The path of an API method
+    pub path: &'a str,
+    /// The `from` address of a message which is being sent
+    pub from: Option<&'a str>,
+}
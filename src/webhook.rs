@@ -0,0 +1,318 @@
+//! Parsing and verification of Postal webhooks.
+//!
+//! The module is available with the `webhook` feature.
+
+use crate::{de::flag, MessageHash, PostalError};
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Public};
+use openssl::sign::Verifier;
+use reqwest::header::HeaderMap;
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value as Json;
+
+/// The name of a header Postal sends a signature of a webhook in
+pub const SIGNATURE_HEADER: &str = "X-Postal-Signature";
+
+/// WebhookEvent is a body of a webhook request sent by Postal
+///
+/// Events which are not known are kept as is in [Unknown].
+///
+/// [Unknown]: #variant.Unknown
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEvent {
+    /// A message was delivered
+    MessageSent(MessageStatusEvent),
+    /// A delivery of a message was delayed and will be retried
+    MessageDelayed(MessageStatusEvent),
+    /// A message couldn't be delivered
+    MessageDeliveryFailed(MessageStatusEvent),
+    /// A message was held
+    MessageHeld(MessageHeldEvent),
+    /// Any other event
+    Unknown {
+        /// The name of the event
+        event: String,
+        /// The payload of the event
+        payload: Json,
+    },
+}
+
+impl<'de> Deserialize<'de> for WebhookEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn parse<T: DeserializeOwned, E: Error>(payload: Json) -> Result<T, E> {
+            serde_json::from_value(payload).map_err(E::custom)
+        }
+
+        let mut body = serde_json::Map::<String, Json>::deserialize(deserializer)?;
+        let event = match body.remove("event") {
+            Some(Json::String(event)) => event,
+            _ => return Err(D::Error::missing_field("event")),
+        };
+        let payload = body.remove("payload").unwrap_or_default();
+
+        match event.as_str() {
+            "MessageSent" => parse(payload).map(Self::MessageSent),
+            "MessageDelayed" => parse(payload).map(Self::MessageDelayed),
+            "MessageDeliveryFailed" => parse(payload).map(Self::MessageDeliveryFailed),
+            "MessageHeld" => {
+                let hold_reason = payload
+                    .get("hold_reason")
+                    .and_then(Json::as_str)
+                    .map(ToOwned::to_owned);
+                let event: MessageStatusEvent = parse(payload)?;
+                let hold_reason = hold_reason
+                    .as_deref()
+                    .or(event.details.as_deref())
+                    .map(HoldReason::from_details)
+                    .unwrap_or(HoldReason::Other(String::new()));
+
+                Ok(Self::MessageHeld(MessageHeldEvent { event, hold_reason }))
+            }
+            _ => Ok(Self::Unknown { event, payload }),
+        }
+    }
+}
+
+/// MessageStatusEvent is a payload of an event about a delivery of a message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageStatusEvent {
+    /// The status of the delivery e.g. `Sent` or `SoftFail`
+    pub status: String,
+    /// A human readable description of the delivery
+    #[serde(default)]
+    pub details: Option<String>,
+    /// An output of the remote server
+    #[serde(default)]
+    pub output: Option<String>,
+    /// How long the delivery took in seconds
+    #[serde(default)]
+    pub time: Option<f64>,
+    /// Was the message sent over a TLS connection?
+    #[serde(default, deserialize_with = "flag")]
+    pub sent_with_ssl: bool,
+    /// A unix timestamp of the event
+    #[serde(default)]
+    pub timestamp: Option<f64>,
+    /// The message the event is about
+    pub message: WebhookMessage,
+}
+
+/// MessageHeldEvent is a payload of a `MessageHeld` event
+///
+/// # Examples
+///
+/// ```
+/// use postal_rs::webhook::{HoldReason, WebhookEvent};
+///
+/// let body = r#"{
+///     "event": "MessageHeld",
+///     "payload": {
+///         "status": "Held",
+///         "details": "Message has a spam score higher than the server's threshold.",
+///         "message": { "id": 12345, "token": "abcdef" }
+///     }
+/// }"#;
+///
+/// match serde_json::from_str(body).unwrap() {
+///     WebhookEvent::MessageHeld(event) => {
+///         assert_eq!(event.hold_reason, HoldReason::Spam);
+///         assert_eq!(
+///             event.details(),
+///             "Message has a spam score higher than the server's threshold."
+///         );
+///         assert_eq!(event.token(), "abcdef");
+///     }
+///     event => panic!("unexpected event {:?}", event),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageHeldEvent {
+    /// The status of the message
+    pub event: MessageStatusEvent,
+    /// Why the message was held
+    pub hold_reason: HoldReason,
+}
+
+impl MessageHeldEvent {
+    /// Returns a description of why the message was held
+    pub fn details(&self) -> &str {
+        self.event.details.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the token of the held message
+    ///
+    /// With the id of the message it can be used to get its details.
+    pub fn token(&self) -> &str {
+        &self.event.message.token
+    }
+}
+
+/// HoldReason is why Postal held a message
+///
+/// Postal sends it as a human readable text in `details`,
+/// a text which is not recognized is kept in [Other].
+///
+/// [Other]: #variant.Other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HoldReason {
+    /// The spam score of the message is higher than the threshold
+    Spam,
+    /// The credential is configured to hold all messages
+    Credential,
+    /// The server is in development mode
+    DevelopmentMode,
+    /// The recipient is on the suppression list
+    Suppressed,
+    /// The server has reached its send limit
+    SendLimit,
+    /// Any other reason
+    Other(String),
+}
+
+impl HoldReason {
+    /// Recognizes a reason by the `details` Postal sends
+    pub fn from_details(details: &str) -> Self {
+        let lower = details.to_lowercase();
+        if lower.contains("spam") {
+            Self::Spam
+        } else if lower.contains("credential") {
+            Self::Credential
+        } else if lower.contains("development mode") {
+            Self::DevelopmentMode
+        } else if lower.contains("suppression") {
+            Self::Suppressed
+        } else if lower.contains("send limit") {
+            Self::SendLimit
+        } else {
+            Self::Other(details.to_owned())
+        }
+    }
+}
+
+/// WebhookMessage describes a message a webhook event is about
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WebhookMessage {
+    /// The id of the message
+    pub id: MessageHash,
+    /// The token of the message
+    pub token: String,
+    /// Either `incoming` or `outgoing`
+    #[serde(default)]
+    pub direction: Option<String>,
+    /// The value of the `Message-ID` header
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// The recipient of the message
+    #[serde(default)]
+    pub to: Option<String>,
+    /// The sender of the message
+    #[serde(default)]
+    pub from: Option<String>,
+    /// The subject of the message
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// A unix timestamp when the message was received
+    #[serde(default)]
+    pub timestamp: Option<f64>,
+    /// The spam status of the message
+    #[serde(default)]
+    pub spam_status: Option<String>,
+    /// The tag of the message
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// WebhookRequest verifies and parses webhook requests
+#[derive(Debug)]
+pub struct WebhookRequest;
+
+impl WebhookRequest {
+    /// Verifies a signature of a webhook request and parses its body
+    ///
+    /// The signature is taken from the [SIGNATURE_HEADER] header.
+    /// The public key is the one shown by Postal,
+    /// either in a PEM form or as a bare base64 string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use openssl::{hash::MessageDigest, pkey::PKey, rsa::Rsa, sign::Signer};
+    /// use postal_rs::webhook::{WebhookEvent, WebhookRequest, SIGNATURE_HEADER};
+    /// use reqwest::header::HeaderMap;
+    ///
+    /// let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    /// let public_key = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+    ///
+    /// let body = br#"{
+    ///     "event": "MessageSent",
+    ///     "timestamp": 1600000000.0,
+    ///     "payload": {
+    ///         "status": "Sent",
+    ///         "details": "Message sent by SMTP",
+    ///         "sent_with_ssl": true,
+    ///         "message": { "id": 12345, "token": "abcdef", "to": "example@gmail.com" }
+    ///     }
+    /// }"#;
+    /// let mut signer = Signer::new(MessageDigest::sha1(), &key).unwrap();
+    /// signer.update(body).unwrap();
+    /// let signature = base64::encode(signer.sign_to_vec().unwrap());
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert(SIGNATURE_HEADER, signature.parse().unwrap());
+    ///
+    /// let event = WebhookRequest::from_parts(&headers, body, &public_key).unwrap();
+    /// match event {
+    ///     WebhookEvent::MessageSent(event) => {
+    ///         assert_eq!(event.message.id, 12345);
+    ///         assert_eq!(event.message.token, "abcdef");
+    ///         assert!(event.sent_with_ssl);
+    ///     }
+    ///     event => panic!("unexpected event {:?}", event),
+    /// }
+    ///
+    /// let tampered = br#"{ "event": "MessageSent" }"#;
+    /// assert!(WebhookRequest::from_parts(&headers, tampered, &public_key).is_err());
+    /// ```
+    ///
+    /// [SIGNATURE_HEADER]: ./constant.SIGNATURE_HEADER.html
+    pub fn from_parts(
+        headers: &HeaderMap,
+        body: &[u8],
+        public_key_pem: &str,
+    ) -> Result<WebhookEvent, PostalError> {
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(PostalError::InvalidSignature)?;
+
+        verify(body, signature, public_key_pem)?;
+
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+/// Verifies a base64 encoded RSA SHA1 signature of a body
+pub fn verify(body: &[u8], signature: &str, public_key: &str) -> Result<(), PostalError> {
+    let key = public_key_from_str(public_key)?;
+    let signature = base64::decode(signature.trim()).map_err(|_| PostalError::InvalidSignature)?;
+
+    let mut verifier = Verifier::new(MessageDigest::sha1(), &key)?;
+    verifier.update(body)?;
+    if verifier.verify(&signature)? {
+        Ok(())
+    } else {
+        Err(PostalError::InvalidSignature)
+    }
+}
+
+fn public_key_from_str(key: &str) -> Result<PKey<Public>, PostalError> {
+    let key = key.trim();
+    if key.starts_with("-----BEGIN") {
+        return Ok(PKey::public_key_from_pem(key.as_bytes())?);
+    }
+
+    let der: String = key.trim_start_matches("p=").split_whitespace().collect();
+    let der = base64::decode(der)?;
+
+    Ok(PKey::public_key_from_der(&der)?)
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::message;
+use postal_rs::{Client, PostalError};
+
+fn invalid_field(err: PostalError) -> (&'static str, String) {
+    match err {
+        PostalError::InvalidAddress { field, value } => (field, value),
+        err => panic!("{:?}", err),
+    }
+}
+
+#[test]
+fn trailing_space_is_trimmed() {
+    let message = message()
+        .to(&[
+            "example@gmail.com ".to_owned(),
+            " another@gmail.com".to_owned(),
+        ])
+        .from("test@yourserver.io\t")
+        .validate_addresses()
+        .unwrap();
+
+    assert_eq!(
+        message.to.unwrap(),
+        ["example@gmail.com", "another@gmail.com"]
+    );
+    assert_eq!(message.from.as_deref(), Some("test@yourserver.io"));
+}
+
+#[test]
+fn missing_at() {
+    let err = message()
+        .to(&["example.gmail.com".to_owned()])
+        .validate_addresses()
+        .unwrap_err();
+
+    assert_eq!(invalid_field(err), ("to", "example.gmail.com".to_owned()));
+}
+
+#[test]
+fn invalid_address_in_each_field() {
+    let mut cc = message();
+    cc.cc = Some(vec!["cc@".to_owned()]);
+    let mut bcc = message();
+    bcc.bcc = Some(vec!["@gmail.com".to_owned()]);
+    let mut reply_to = message();
+    reply_to.reply_to = Some("reply@gmail..com".to_owned());
+    let from = message().from("test@yourserver io");
+
+    let cases = vec![
+        (cc, "cc"),
+        (bcc, "bcc"),
+        (reply_to, "reply_to"),
+        (from, "from"),
+    ];
+    for (message, field) in cases {
+        let err = message.validate_addresses().unwrap_err();
+        assert_eq!(invalid_field(err).0, field);
+    }
+}
+
+#[test]
+fn unusual_addresses_are_accepted() {
+    let to = vec![
+        "\"john doe\"@gmail.com".to_owned(),
+        "John Doe <john@gmail.com>".to_owned(),
+        "user+tag@sub.example.co.uk".to_owned(),
+    ];
+
+    let message = message().to(&to).validate_addresses().unwrap();
+
+    assert_eq!(message.to.unwrap(), to);
+}
+
+#[cfg(not(feature = "idna"))]
+#[test]
+fn idn_domain_is_kept() {
+    let message = message()
+        .to(&["user@münchen.de".to_owned()])
+        .validate_addresses()
+        .unwrap();
+
+    assert_eq!(message.to.unwrap(), ["user@münchen.de"]);
+}
+
+#[test]
+fn validation_is_opt_in() {
+    let message = message().to(&["example.gmail.com".to_owned()]);
+
+    let client = Client::new("http://127.0.0.1:1", "token").unwrap();
+    assert!(client.send_dry_run(message.clone()).is_ok());
+
+    let client = Client::builder("http://127.0.0.1:1", "token")
+        .validate_addresses(true)
+        .build()
+        .unwrap();
+    let err = client.send_dry_run(message).unwrap_err();
+    assert_eq!(invalid_field(err).0, "to");
+}
+
+#[cfg(feature = "idna")]
+#[test]
+fn idn_domain_is_converted() {
+    let mut message = message()
+        .to(&["user@münchen.de".to_owned()])
+        .from("Müller <müller@münchen.de>");
+    message.cc = Some(vec!["cc@MÜNCHEN.de".to_owned()]);
+    message.bcc = Some(vec!["bcc@bücher.example".to_owned()]);
+    message.reply_to = Some("reply@münchen.de".to_owned());
+
+    let message = message.validate_addresses().unwrap();
+
+    assert_eq!(message.to.unwrap(), ["user@xn--mnchen-3ya.de"]);
+    assert_eq!(message.cc.unwrap(), ["cc@xn--mnchen-3ya.de"]);
+    assert_eq!(message.bcc.unwrap(), ["bcc@xn--bcher-kva.example"]);
+    assert_eq!(message.reply_to.as_deref(), Some("reply@xn--mnchen-3ya.de"));
+    // the local part and the display name are kept
+    assert_eq!(
+        message.from.as_deref(),
+        Some("Müller <müller@xn--mnchen-3ya.de>")
+    );
+}
+
+#[cfg(feature = "idna")]
+#[test]
+fn ascii_domain_is_kept() {
+    let message = message().validate_addresses().unwrap();
+
+    assert_eq!(message.to.unwrap(), ["example@gmail.com"]);
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::message;
+use postal_rs::testing::MockPostal;
+use postal_rs::{Attachment, EncodedAttachment, Message, MessageAttachment};
+use std::io::Read;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn large_attachment_from_reader() {
+    let size = 8 * 1024 * 1024;
+    let content: Vec<u8> = (0..=255u8).cycle().take(size).collect();
+    let attachment =
+        Attachment::from_reader("report.pdf", "application/pdf", &content[..]).unwrap();
+    assert_eq!(attachment.data, content);
+
+    let mock = MockPostal::start().await;
+    mock.client()
+        .send(message().attachment(attachment))
+        .await
+        .unwrap();
+
+    let json = mock.requests()[0].json().unwrap();
+    let sent = &json["attachments"][0];
+    assert_eq!(sent["name"], "report.pdf");
+    assert_eq!(sent["content_type"], "application/pdf");
+
+    let data = base64::decode(sent["data"].as_str().unwrap()).unwrap();
+    assert!(data == content);
+}
+
+#[tokio::test]
+async fn encoded_attachment_is_encoded_once() {
+    let content: Vec<u8> = (0..=255u8).cycle().take(64 * 1024).collect();
+    let report = EncodedAttachment::new(Attachment::new("report.pdf", "application/pdf", content));
+    let encoded = report.encoded().clone();
+
+    let first = message().attachment(report);
+    let messages: Vec<Message> = (0..3).map(|_| first.clone()).collect();
+
+    // clones of the message share the encoded content
+    assert_eq!(Arc::strong_count(&encoded), 5);
+    for message in &messages {
+        match &message.attachments.as_ref().unwrap()[0] {
+            MessageAttachment::Encoded(attachment) => {
+                assert!(Arc::ptr_eq(attachment.encoded(), &encoded))
+            }
+            attachment => panic!("{:?}", attachment),
+        }
+    }
+
+    let mock = MockPostal::start().await;
+    let client = mock.client();
+    for message in messages {
+        client.send(message).await.unwrap();
+    }
+
+    for request in mock.requests() {
+        let json = request.json().unwrap();
+        assert_eq!(json["attachments"][0]["data"], &*encoded);
+        assert_eq!(json["attachments"][0]["name"], "report.pdf");
+    }
+    assert_eq!(mock.requests().len(), 3);
+    assert_eq!(Arc::strong_count(&encoded), 2);
+}
+
+#[test]
+fn failing_reader() {
+    struct Broken;
+
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken"))
+        }
+    }
+
+    let err = Attachment::from_reader("a.bin", "application/octet-stream", Broken).unwrap_err();
+
+    assert!(matches!(err, postal_rs::PostalError::Io(_)), "{:?}", err);
+}
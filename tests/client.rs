@@ -0,0 +1,133 @@
+use postal_rs::{Client, PostalError};
+
+const TOKEN: &str = "s3cr3t-api-token";
+
+#[test]
+fn debug_redacts_token() {
+    let client = Client::new("https://postal.example.com", TOKEN).unwrap();
+
+    for debug in &[format!("{:?}", client), format!("{:#?}", client)] {
+        assert!(!debug.contains(TOKEN), "{}", debug);
+        assert!(debug.contains("***"), "{}", debug);
+        assert!(debug.contains("postal.example.com"), "{}", debug);
+    }
+}
+
+#[test]
+fn debug_of_builder_redacts_token() {
+    let builder = Client::builder("https://postal.example.com", TOKEN);
+
+    let debug = format!("{:?}", builder);
+
+    assert!(!debug.contains(TOKEN), "{}", debug);
+    assert!(debug.contains("postal.example.com"), "{}", debug);
+}
+
+#[test]
+fn debug_of_derived_client_redacts_token() {
+    let client = Client::new("https://postal.example.com", "first-token").unwrap();
+    let derived = client.with_token(TOKEN).unwrap();
+
+    let debug = format!("{:?}", derived);
+
+    assert!(!debug.contains(TOKEN), "{}", debug);
+}
+
+fn send_url(client: &Client) -> String {
+    let request = client
+        .request_builder("api/v1/send/message")
+        .unwrap()
+        .build()
+        .unwrap();
+    request.url().to_string()
+}
+
+#[test]
+fn base_url_with_and_without_trailing_slash() {
+    let cases = [
+        (
+            "https://postal.example.com",
+            "https://postal.example.com/api/v1/send/message",
+        ),
+        (
+            "https://postal.example.com/",
+            "https://postal.example.com/api/v1/send/message",
+        ),
+        (
+            "https://example.com/postal",
+            "https://example.com/postal/api/v1/send/message",
+        ),
+        (
+            "https://example.com/postal/",
+            "https://example.com/postal/api/v1/send/message",
+        ),
+        (
+            "https://example.com/a/postal",
+            "https://example.com/a/postal/api/v1/send/message",
+        ),
+    ];
+
+    for (base, expected) in &cases {
+        let client = Client::new(base, TOKEN).unwrap();
+        assert_eq!(send_url(&client), *expected, "base {}", base);
+    }
+}
+
+#[test]
+fn derived_base_url_keeps_its_prefix() {
+    let client = Client::new("https://postal.example.com", TOKEN).unwrap();
+
+    let eu = client
+        .with_base_url("https://eu.example.com/postal")
+        .unwrap();
+
+    assert_eq!(
+        send_url(&eu),
+        "https://eu.example.com/postal/api/v1/send/message"
+    );
+    assert_eq!(
+        send_url(&client),
+        "https://postal.example.com/api/v1/send/message"
+    );
+}
+
+#[test]
+fn base_url_without_scheme() {
+    let cases = [
+        (
+            "postal.example.com",
+            "https://postal.example.com/api/v1/send/message",
+        ),
+        (
+            "postal.example.com:8080",
+            "https://postal.example.com:8080/api/v1/send/message",
+        ),
+        (
+            "postal.example.com/postal?next=http://x",
+            "https://postal.example.com/postal/api/v1/send/message",
+        ),
+        (
+            "http://postal.example.com",
+            "http://postal.example.com/api/v1/send/message",
+        ),
+    ];
+
+    for (base, expected) in &cases {
+        let client = Client::new(base, TOKEN).unwrap();
+        assert_eq!(send_url(&client), *expected, "base {}", base);
+    }
+}
+
+#[test]
+fn base_url_without_host() {
+    for base in &["", "https://", "file:///postal"] {
+        let err = Client::new(base, TOKEN).unwrap_err();
+
+        assert!(
+            matches!(err, PostalError::UrlIssue(url::ParseError::EmptyHost)),
+            "base {:?}: {:?}",
+            base,
+            err
+        );
+    }
+}
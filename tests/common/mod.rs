@@ -0,0 +1,19 @@
+//! Helpers shared by the integration tests
+
+// each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use postal_rs::Message;
+
+/// Returns a message which can be sent
+pub fn message() -> Message {
+    Message::default()
+        .to(&["example@gmail.com".to_owned()])
+        .from("test@yourserver.io")
+        .text("A test message")
+}
+
+/// Returns `count` distinct addresses
+pub fn addresses(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("user{}@gmail.com", i)).collect()
+}
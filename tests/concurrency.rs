@@ -0,0 +1,107 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::message;
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use postal_rs::Client;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Sends a message from each of `tasks` spawned tasks
+/// and returns how many connections were used
+async fn connections(client: Client, mock: &MockPostal, tasks: usize) -> usize {
+    let handles: Vec<_> = (0..tasks)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.send(message()).await })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    assert_eq!(mock.requests().len(), tasks);
+    mock.requests()
+        .iter()
+        .map(|request| request.peer)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+fn client(mock: &MockPostal, limit: Option<usize>) -> Client {
+    let builder = Client::builder(mock.url(), MOCK_TOKEN);
+    let builder = match limit {
+        Some(limit) => builder.max_concurrent_requests(limit),
+        None => builder,
+    };
+    builder.build().unwrap()
+}
+
+#[tokio::test]
+async fn requests_are_serialized() {
+    let mock = MockPostal::start().await;
+
+    let used = connections(client(&mock, Some(1)), &mock, 8).await;
+
+    // each request waits for the previous one, so the connection is reused
+    assert_eq!(used, 1);
+}
+
+#[tokio::test]
+async fn requests_are_limited() {
+    let mock = MockPostal::start().await;
+
+    let used = connections(client(&mock, Some(2)), &mock, 8).await;
+
+    assert!(used <= 2, "{}", used);
+}
+
+#[tokio::test]
+async fn unlimited_by_default() {
+    let mock = MockPostal::start().await;
+
+    let used = connections(client(&mock, None), &mock, 8).await;
+
+    assert!(used > 2, "{}", used);
+}
+
+#[tokio::test]
+async fn concurrent_shutdowns() {
+    let mut listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+        .await
+        .unwrap();
+    let client = Client::new(
+        format!("http://{}", listener.local_addr().unwrap()),
+        MOCK_TOKEN,
+    )
+    .unwrap();
+    let sending = tokio::spawn({
+        let client = client.clone();
+        async move { client.send(message()).await }
+    });
+
+    // the request is in flight until the connection is closed
+    let (connection, _) = listener.accept().await.unwrap();
+    let shutdowns: Vec<_> = (0..2)
+        .map(|_| tokio::spawn(client.clone().shutdown()))
+        .collect();
+    tokio::time::delay_for(Duration::from_millis(50)).await;
+    drop(connection);
+
+    assert!(sending.await.unwrap().is_err());
+    for shutdown in shutdowns {
+        tokio::time::timeout(Duration::from_secs(5), shutdown)
+            .await
+            .expect("shutdown must not hang")
+            .unwrap();
+    }
+}
+
+#[test]
+#[should_panic(expected = "must be positive")]
+fn zero_limit() {
+    Client::builder("http://127.0.0.1:1", MOCK_TOKEN).max_concurrent_requests(0);
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "testing")]
+
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use postal_rs::{Client, DetailsInterest, MessageStatus, PostalError};
+use serde_json::json;
+use std::collections::HashSet;
+
+const DELIVERIES: &str = "/api/v1/messages/deliveries";
+
+fn client(mock: &MockPostal, concurrency: usize) -> Client {
+    Client::builder(mock.url(), MOCK_TOKEN)
+        .batch_concurrency(concurrency)
+        .build()
+        .unwrap()
+}
+
+fn delivery(id: u64, status: &str) -> serde_json::Value {
+    json!({ "id": id, "status": status, "output": "250 OK", "timestamp": 1600000000.0 })
+}
+
+#[tokio::test]
+async fn per_id_results() {
+    let mock = MockPostal::start().await;
+    mock.respond_success(DELIVERIES, json!([delivery(10, "Sent")]));
+    mock.respond_error(DELIVERIES, "MessageNotFound", "No message found");
+    mock.respond_success(
+        DELIVERIES,
+        json!([delivery(30, "SoftFail"), delivery(31, "Sent")]),
+    );
+
+    let results = client(&mock, 1).get_deliveries_batch(&[1, 2, 3]).await;
+
+    assert_eq!(results.len(), 3);
+
+    let (id, deliveries) = results[0].as_ref().unwrap();
+    assert_eq!(*id, 1);
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].id, 10);
+
+    assert!(results[1].as_ref().unwrap_err().is_not_found());
+
+    let (id, deliveries) = results[2].as_ref().unwrap();
+    assert_eq!(*id, 3);
+    let statuses: Vec<_> = deliveries.iter().map(|d| d.status.as_str()).collect();
+    assert_eq!(statuses, ["SoftFail", "Sent"]);
+
+    let requested: Vec<_> = mock
+        .requests()
+        .iter()
+        .map(|request| request.json().unwrap()["id"].clone())
+        .collect();
+    assert_eq!(requested, [1, 2, 3]);
+}
+
+#[tokio::test]
+async fn results_keep_the_order_of_ids() {
+    let mock = MockPostal::start().await;
+    let ids: Vec<u64> = (1..=20).collect();
+
+    let results = client(&mock, 5).get_deliveries_batch(&ids).await;
+
+    let got: Vec<u64> = results.into_iter().map(|r| r.unwrap().0).collect();
+    assert_eq!(got, ids);
+}
+
+#[tokio::test]
+async fn requests_are_concurrent() {
+    let peers = |mock: &MockPostal| {
+        mock.requests()
+            .iter()
+            .map(|request| request.peer)
+            .collect::<HashSet<_>>()
+            .len()
+    };
+
+    let mock = MockPostal::start().await;
+    client(&mock, 1).get_deliveries_batch(&[1, 2, 3, 4]).await;
+    // a single connection is reused by sequential requests
+    assert_eq!(peers(&mock), 1);
+
+    let mock = MockPostal::start().await;
+    client(&mock, 4).get_deliveries_batch(&[1, 2, 3, 4]).await;
+    // concurrent requests need their own connections
+    assert!(peers(&mock) > 1);
+}
+
+#[tokio::test]
+async fn empty_batch() {
+    let mock = MockPostal::start().await;
+
+    assert!(mock.client().get_deliveries_batch(&[]).await.is_empty());
+    assert!(mock.requests().is_empty());
+}
+
+const DETAILS: &str = "/api/v1/messages/message";
+
+#[tokio::test]
+async fn message_report() {
+    let mock = MockPostal::start().await;
+    mock.respond_success(
+        DETAILS,
+        json!({ "id": 7, "token": "abc", "status": { "status": "Sent", "held": 0 } }),
+    );
+    mock.respond_success(DELIVERIES, json!([delivery(70, "Sent")]));
+
+    let report = mock
+        .client()
+        .get_message_report(DetailsInterest::new(7).with_status())
+        .await
+        .unwrap();
+
+    assert_eq!(report.details.id, 7);
+    assert_eq!(report.details.status.unwrap().status, MessageStatus::Sent);
+    assert_eq!(report.deliveries.len(), 1);
+    assert_eq!(report.deliveries[0].id, 70);
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        assert_eq!(request.json().unwrap()["id"], 7);
+    }
+    let details = requests.iter().find(|r| r.path == DETAILS).unwrap();
+    assert_eq!(details.json().unwrap()["_expansions"], json!(["status"]));
+    // both requests are in flight at once
+    assert_ne!(requests[0].peer, requests[1].peer);
+}
+
+#[tokio::test]
+async fn message_report_fails_on_details() {
+    let mock = MockPostal::start().await;
+    mock.respond_error(DETAILS, "MessageNotFound", "No message found");
+
+    let err = mock.client().get_message_report(7).await.unwrap_err();
+
+    assert!(err.is_not_found(), "{:?}", err);
+}
+
+#[tokio::test]
+async fn message_report_fails_on_deliveries() {
+    let mock = MockPostal::start().await;
+    mock.respond(DELIVERIES, 500, "");
+
+    let err = mock.client().get_message_report(7).await.unwrap_err();
+
+    assert!(matches!(err, PostalError::InternalServerError), "{:?}", err);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::{addresses, message};
+use postal_rs::{Client, Message, PostalError, MAX_RECIPIENTS};
+
+// nothing listens on the port, so any request would fail
+fn client() -> Client {
+    Client::new("http://127.0.0.1:1", "token").unwrap()
+}
+
+#[test]
+fn valid_message() {
+    let prepared = client().send_dry_run(message()).unwrap();
+
+    assert_eq!(prepared, message());
+    let json: serde_json::Value = serde_json::from_slice(&prepared.to_json().unwrap()).unwrap();
+    assert_eq!(json["to"][0], "example@gmail.com");
+    assert_eq!(json["from"], "test@yourserver.io");
+}
+
+#[test]
+fn defaults_are_filled() {
+    let client = Client::builder("http://127.0.0.1:1", "token")
+        .default_from("noreply@yourserver.io")
+        .build()
+        .unwrap();
+    let mut message = message();
+    message.from = None;
+
+    let prepared = client.send_dry_run(message).unwrap();
+
+    assert_eq!(prepared.from.as_deref(), Some("noreply@yourserver.io"));
+}
+
+#[test]
+fn too_many_recipients() {
+    let lists: [fn(Message, Vec<String>) -> Message; 3] = [
+        |message, list| message.to(&list),
+        |mut message, list| {
+            message.cc = Some(list);
+            message
+        },
+        |mut message, list| {
+            message.bcc = Some(list);
+            message
+        },
+    ];
+
+    for set in lists.iter() {
+        let full = set(message(), addresses(MAX_RECIPIENTS));
+        assert!(client().send_dry_run(full).is_ok());
+
+        let over = set(message(), addresses(MAX_RECIPIENTS + 1));
+        let err = client().send_dry_run(over).unwrap_err();
+        assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+    }
+}
+
+#[test]
+fn no_recipients() {
+    let err = client().send_dry_run(message().to(&[])).unwrap_err();
+
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+}
+
+#[test]
+fn no_from() {
+    let mut message = message();
+    message.from = None;
+
+    let err = client().send_dry_run(message).unwrap_err();
+
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+}
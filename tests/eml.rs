@@ -0,0 +1,85 @@
+use postal_rs::{PostalError, RawMessage};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[test]
+fn recipients_and_sender_from_headers() {
+    let message = RawMessage::from_eml_file(fixture("message.eml"), None, None).unwrap();
+
+    assert_eq!(
+        message.rcpt_to,
+        ["example@gmail.com", "another@gmail.com", "copy@gmail.com"]
+    );
+    assert_eq!(message.mail_from, "bounces@yourserver.io");
+    assert_eq!(message.bounce, None);
+
+    let data = std::fs::read(fixture("message.eml")).unwrap();
+    assert_eq!(base64::decode(&message.data).unwrap(), data);
+}
+
+#[test]
+fn given_recipients_and_sender() {
+    let rcpt_to = ["other@gmail.com".to_owned()];
+    let message = RawMessage::from_eml_file(
+        fixture("message.eml"),
+        Some(&rcpt_to),
+        Some("relay@yourserver.io"),
+    )
+    .unwrap();
+
+    assert_eq!(message.rcpt_to, rcpt_to);
+    assert_eq!(message.mail_from, "relay@yourserver.io");
+}
+
+#[test]
+fn null_return_path() {
+    let message = RawMessage::from_eml_file(fixture("bounce.eml"), None, None).unwrap();
+
+    assert_eq!(message.mail_from, "mailer-daemon@yourserver.io");
+    assert_eq!(message.rcpt_to, ["test@yourserver.io"]);
+    assert_eq!(message.bounce, Some(true));
+}
+
+#[test]
+fn only_null_return_path() {
+    let data = b"Return-Path: <>\r\nTo: test@yourserver.io\r\n\r\nHi\r\n";
+    let err = RawMessage::from_eml_bytes(data, None, None).unwrap_err();
+
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+}
+
+#[test]
+fn no_recipients() {
+    let data = b"From: test@yourserver.io\r\nSubject: Hi\r\n\r\nHi\r\n";
+    let err = RawMessage::from_eml_bytes(data, None, None).unwrap_err();
+
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+}
+
+#[test]
+fn folded_headers() {
+    let data = b"From: test@yourserver.io\r\nTo: a@gmail.com,\r\n\tb@gmail.com\r\n\r\nHi\r\n";
+    let message = RawMessage::from_eml_bytes(data, None, None).unwrap();
+
+    assert_eq!(message.rcpt_to, ["a@gmail.com", "b@gmail.com"]);
+    assert_eq!(message.mail_from, "test@yourserver.io");
+}
+
+#[test]
+fn missing_file() {
+    let err = RawMessage::from_eml_file(fixture("missing.eml"), None, None).unwrap_err();
+
+    match err {
+        PostalError::Io(ref io) => {
+            assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+            assert_eq!(err.to_string(), format!("i/o error: {}", io));
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+}
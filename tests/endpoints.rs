@@ -0,0 +1,90 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::message;
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use postal_rs::{Client, Endpoints};
+
+fn paths(mock: &MockPostal) -> Vec<String> {
+    mock.requests().into_iter().map(|r| r.path).collect()
+}
+
+#[tokio::test]
+async fn default_endpoints_under_a_prefix() {
+    let mock = MockPostal::start().await;
+    let client = Client::new(format!("{}postal", mock.url()), MOCK_TOKEN).unwrap();
+
+    client.send(message()).await.unwrap();
+    client.get_message_details(1).await.unwrap();
+    client.get_message_deliveries(1).await.unwrap();
+
+    assert_eq!(
+        paths(&mock),
+        [
+            "/postal/api/v1/send/message",
+            "/postal/api/v1/messages/message",
+            "/postal/api/v1/messages/deliveries",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn overridden_endpoints() {
+    let mock = MockPostal::start().await;
+    mock.respond_success(
+        "/postal/api/v2/send",
+        serde_json::json!({ "message_id": "mock@postal", "messages": {} }),
+    );
+    mock.respond_success(
+        "/other/send/raw",
+        serde_json::json!({ "message_id": "mock@postal", "messages": {} }),
+    );
+    let client = Client::builder(format!("{}postal/", mock.url()), MOCK_TOKEN)
+        .endpoints(Endpoints {
+            // a relative path keeps the prefix of the base URL
+            send_message: "api/v2/send".to_owned(),
+            // an absolute one replaces it
+            send_raw: "/other/send/raw".to_owned(),
+            ..Endpoints::default()
+        })
+        .build()
+        .unwrap();
+
+    client.send(message()).await.unwrap();
+    client
+        .send_raw(message().into_raw_message().unwrap())
+        .await
+        .unwrap();
+    client.get_message_details(1).await.unwrap();
+
+    assert_eq!(
+        paths(&mock),
+        [
+            "/postal/api/v2/send",
+            "/other/send/raw",
+            "/postal/api/v1/messages/message",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn endpoints_of_a_derived_client() {
+    let mock = MockPostal::start().await;
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .endpoints(Endpoints {
+            send_message: "api/v2/send".to_owned(),
+            ..Endpoints::default()
+        })
+        .build()
+        .unwrap();
+    mock.respond_success(
+        "/eu/api/v2/send",
+        serde_json::json!({ "message_id": "mock@postal", "messages": {} }),
+    );
+
+    let eu = client.with_base_url(format!("{}eu", mock.url())).unwrap();
+    eu.send(message()).await.unwrap();
+
+    assert_eq!(paths(&mock), ["/eu/api/v2/send"]);
+}
@@ -0,0 +1,288 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::message;
+use postal_rs::testing::MockPostal;
+use postal_rs::{PostalError, PostalErrorCode, RateLimitInfo};
+use reqwest::StatusCode;
+use std::time::{Duration, SystemTime};
+
+const SEND: &str = "/api/v1/send/message";
+
+async fn send_with_status(status: u16) -> PostalError {
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, status, "");
+
+    mock.client().send(message()).await.unwrap_err()
+}
+
+#[tokio::test]
+async fn rate_limited() {
+    let err = send_with_status(429).await;
+
+    assert!(
+        matches!(&err, PostalError::RateLimited(info) if *info == RateLimitInfo::default()),
+        "{:?}",
+        err
+    );
+    assert!(err.is_retriable());
+    assert_eq!(err.retry_after(), None);
+}
+
+#[tokio::test]
+async fn rate_limited_with_headers() {
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, 429, "");
+    mock.respond_with_headers(
+        SEND,
+        &[
+            ("X-RateLimit-Limit", "100"),
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset", "60"),
+            ("Retry-After", "30"),
+        ],
+    );
+    let client = mock.client();
+
+    let err = client.send(message()).await.unwrap_err();
+
+    let info = RateLimitInfo {
+        limit: Some(100),
+        remaining: Some(0),
+        reset: Some(60),
+        retry_after: Some(30),
+    };
+    assert!(
+        matches!(&err, PostalError::RateLimited(got) if *got == info),
+        "{:?}",
+        err
+    );
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+    assert_eq!(client.rate_limit(), Some(info));
+}
+
+#[tokio::test]
+async fn retry_after_date() {
+    let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, 429, "");
+    mock.respond_with_headers(SEND, &[("Retry-After", date.as_str())]);
+
+    let err = mock.client().send(message()).await.unwrap_err();
+
+    let retry_after = err.retry_after().unwrap();
+    assert!(
+        retry_after > Duration::from_secs(100) && retry_after <= Duration::from_secs(120),
+        "{:?}",
+        retry_after
+    );
+}
+
+#[tokio::test]
+async fn server_errors() {
+    let err = send_with_status(500).await;
+    assert!(matches!(err, PostalError::InternalServerError), "{:?}", err);
+    assert!(err.is_retriable());
+
+    let err = send_with_status(503).await;
+    assert!(
+        matches!(err, PostalError::ServiceUnavailableError),
+        "{:?}",
+        err
+    );
+    assert!(err.is_retriable());
+}
+
+#[tokio::test]
+async fn gateway_errors() {
+    for &status in &[502, 504] {
+        let err = send_with_status(status).await;
+
+        assert!(
+            matches!(err, PostalError::UnexpectedStatus(s) if s.as_u16() == status),
+            "{:?}",
+            err
+        );
+        assert!(err.is_retriable());
+    }
+}
+
+#[tokio::test]
+async fn unexpected_statuses() {
+    for &status in &[401, 403, 404, 418] {
+        let err = send_with_status(status).await;
+
+        assert!(
+            matches!(err, PostalError::UnexpectedStatus(s) if s.as_u16() == status),
+            "{:?}",
+            err
+        );
+        assert!(!err.is_retriable());
+    }
+}
+
+#[tokio::test]
+async fn redirects() {
+    let err = send_with_status(308).await;
+
+    assert!(
+        matches!(err, PostalError::ExpectedAlternativeUrl),
+        "{:?}",
+        err
+    );
+    assert!(!err.is_retriable());
+}
+
+#[tokio::test]
+async fn unexpected_status_message() {
+    let err = PostalError::UnexpectedStatus(StatusCode::NOT_FOUND);
+
+    assert_eq!(
+        err.to_string(),
+        "postal returned an unexpected status 404 Not Found"
+    );
+}
+
+#[tokio::test]
+async fn error_keeps_raw_body() {
+    let body = serde_json::json!({
+        "status": "error",
+        "time": 0.02,
+        "flags": { "ignore_error_details": 0 },
+        "data": {
+            "code": "ValidationError",
+            "message": "The message couldn't be validated",
+            "errors": { "to": ["is invalid"] }
+        }
+    })
+    .to_string();
+
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, 200, body.clone());
+
+    let err = mock.client().send(message()).await.unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "send error(\"ValidationError\"): \"The message couldn't be validated\""
+    );
+    match err {
+        PostalError::Error { code, message, raw } => {
+            assert_eq!(code, PostalErrorCode::ValidationError);
+            assert_eq!(message, "The message couldn't be validated");
+            assert_eq!(raw.as_deref(), Some(body.as_str()));
+        }
+        err => panic!("{:?}", err),
+    }
+}
+
+#[test]
+fn known_error_codes() {
+    let codes = [
+        ("AccessDenied", PostalErrorCode::AccessDenied),
+        ("InvalidServerAPIKey", PostalErrorCode::InvalidServerAPIKey),
+        ("MessageNotFound", PostalErrorCode::MessageNotFound),
+        ("NoRecipients", PostalErrorCode::NoRecipients),
+        ("TooManyToAddresses", PostalErrorCode::TooManyToAddresses),
+        (
+            "UnauthenticatedFromAddress",
+            PostalErrorCode::UnauthenticatedFromAddress,
+        ),
+    ];
+
+    for (raw, code) in codes.iter() {
+        let parsed = PostalErrorCode::from(raw.to_string());
+        assert_eq!(parsed, *code);
+        assert_eq!(parsed.as_str(), *raw);
+        assert_eq!(parsed.to_string(), *raw);
+    }
+}
+
+#[test]
+fn unknown_error_code() {
+    let code = PostalErrorCode::from("QuotaExceeded".to_owned());
+
+    assert_eq!(code, PostalErrorCode::Other("QuotaExceeded".to_owned()));
+    assert_eq!(code.to_string(), "QuotaExceeded");
+}
+
+#[tokio::test]
+async fn error_codes_of_responses() {
+    let mock = MockPostal::start().await;
+    mock.respond_error(
+        SEND,
+        "InvalidServerAPIKey",
+        "The API token provided was not valid",
+    );
+    mock.respond_error(SEND, "QuotaExceeded", "The quota is exceeded");
+    let client = mock.client();
+
+    let err = client.send(message()).await.unwrap_err();
+    assert!(err.is_auth_error(), "{:?}", err);
+    assert!(matches!(
+        err,
+        PostalError::Error {
+            code: PostalErrorCode::InvalidServerAPIKey,
+            ..
+        }
+    ));
+
+    let err = client.send(message()).await.unwrap_err();
+    assert!(!err.is_auth_error(), "{:?}", err);
+    assert!(matches!(
+        &err,
+        PostalError::Error { code: PostalErrorCode::Other(code), .. } if code == "QuotaExceeded"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "send error(\"QuotaExceeded\"): \"The quota is exceeded\""
+    );
+}
+
+#[tokio::test]
+async fn unknown_response_status() {
+    let body = serde_json::json!({
+        "status": "maintenance",
+        "time": 0.01,
+        "data": { "message": "Postal is under maintenance" }
+    })
+    .to_string();
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, 200, body.clone());
+
+    let err = mock.client().send(message()).await.unwrap_err();
+
+    match err {
+        PostalError::Error { code, message, raw } => {
+            assert_eq!(code, PostalErrorCode::Other("maintenance".to_owned()));
+            assert_eq!(message, "Postal is under maintenance");
+            assert_eq!(raw.as_deref(), Some(body.as_str()));
+        }
+        err => panic!("{:?}", err),
+    }
+}
+
+#[tokio::test]
+async fn extra_response_fields() {
+    let body = serde_json::json!({
+        "status": "success",
+        "time": 0.01,
+        "flags": {},
+        "debug": { "server": "postal-1" },
+        "data": {
+            "message_id": "x@postal",
+            "messages": { "example@gmail.com": { "id": 1, "token": "abc", "queued": true } },
+            "warnings": []
+        }
+    });
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, 200, body.to_string());
+
+    let results = mock.client().send(message()).await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, 1);
+    assert_eq!(results[0].token, "abc");
+}
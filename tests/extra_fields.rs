@@ -0,0 +1,107 @@
+use postal_rs::{Message, PostalError, WireProfile};
+use serde_json::{json, Value as Json};
+
+#[test]
+fn unmodeled_field_is_serialized() {
+    let message = Message::default()
+        .to(&["example@gmail.com".to_owned()])
+        .extra_field("track_opens", json!(true))
+        .unwrap()
+        .extra_field("track_opens", json!(false))
+        .unwrap();
+
+    let json: Json = serde_json::from_slice(&message.to_json().unwrap()).unwrap();
+
+    assert_eq!(
+        json,
+        json!({ "to": ["example@gmail.com"], "track_opens": false })
+    );
+    assert_eq!(WireProfile::TextHtml.serialize(&message).unwrap(), json);
+}
+
+#[test]
+fn modeled_fields_collide() {
+    let fields = [
+        "to",
+        "cc",
+        "bcc",
+        "from",
+        "sender",
+        "subject",
+        "tag",
+        "reply_to",
+        "plain_body",
+        "html_body",
+        "attachments",
+        "headers",
+        "bounce",
+        "charset",
+    ];
+
+    for field in fields.iter() {
+        let err = Message::default()
+            .extra_field(*field, json!("value"))
+            .unwrap_err();
+        assert!(matches!(err, PostalError::InvalidMessage(_)), "{}", field);
+    }
+}
+
+#[test]
+fn modeled_fields_set_directly_collide() {
+    let mut message = Message::default().to(&["example@gmail.com".to_owned()]);
+    message
+        .extra
+        .insert("to".to_owned(), json!(["a@gmail.com"]));
+
+    let err = message.to_json().unwrap_err();
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+    let err = WireProfile::Postal.serialize(&message).unwrap_err();
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+}
+
+#[test]
+fn renamed_fields_collide() {
+    let mut message = Message::default().text("Hi");
+    message.extra.insert("text".to_owned(), json!("Hello"));
+
+    assert!(WireProfile::Postal.serialize(&message).is_ok());
+    let err = WireProfile::TextHtml.serialize(&message).unwrap_err();
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+}
+
+#[test]
+fn no_duplicate_keys() {
+    let message = Message::default()
+        .to(&["example@gmail.com".to_owned()])
+        .extra_field("track_opens", json!(true))
+        .unwrap();
+
+    let json = String::from_utf8(message.to_json().unwrap()).unwrap();
+
+    assert_eq!(json.matches("\"to\"").count(), 1);
+}
+
+#[test]
+fn unknown_fields_are_kept_on_load() {
+    let message: Message =
+        serde_json::from_str(r#"{"to": ["example@gmail.com"], "track_opens": true}"#).unwrap();
+
+    assert_eq!(
+        message.to.as_deref(),
+        Some(&["example@gmail.com".to_owned()][..])
+    );
+    assert_eq!(message.extra().len(), 1);
+    assert_eq!(message.extra()["track_opens"], true);
+}
+
+#[test]
+fn charset_is_not_an_extra_field() {
+    let message: Message =
+        serde_json::from_str(r#"{"to": ["example@gmail.com"], "charset": "iso-8859-1"}"#).unwrap();
+
+    assert_eq!(message.charset.as_deref(), Some("iso-8859-1"));
+    assert!(message.extra.is_empty());
+
+    let json: Json = serde_json::from_slice(&message.to_json().unwrap()).unwrap();
+    assert_eq!(json, json!({ "to": ["example@gmail.com"] }));
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::message;
+use futures::stream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Response, Server};
+use postal_rs::{Client, PostalError};
+use std::convert::Infallible;
+
+const CHUNK: usize = 512;
+const CHUNKS: usize = 16;
+
+/// Starts a server which answers with a body streamed in chunks,
+/// so the response has no `Content-Length`
+fn start_streaming() -> String {
+    let make_service = make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|_| async {
+            let envelope = serde_json::json!({
+                "status": "success",
+                "time": 0.01,
+                "flags": {},
+                "data": { "message_id": "mock@postal", "messages": {} },
+            });
+            let mut envelope = envelope.to_string().into_bytes();
+            envelope.resize(CHUNK, b' ');
+            // the padding keeps the body valid JSON
+            let padding = (1..CHUNKS).map(|_| vec![b' '; CHUNK]);
+            let chunks = std::iter::once(envelope)
+                .chain(padding)
+                .map(Ok::<_, Infallible>);
+
+            Ok::<_, Infallible>(Response::new(Body::wrap_stream(stream::iter(chunks))))
+        }))
+    });
+
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+
+    url
+}
+
+fn client(url: &str, limit: usize) -> Client {
+    Client::builder(url, "token")
+        .max_response_bytes(limit)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn streamed_response_over_limit() {
+    let url = start_streaming();
+
+    let err = client(&url, 1024).send(message()).await.unwrap_err();
+
+    assert!(
+        matches!(err, PostalError::ResponseTooLarge { limit: 1024 }),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn streamed_response_within_limit() {
+    let url = start_streaming();
+
+    let results = client(&url, CHUNK * CHUNKS).send(message()).await.unwrap();
+
+    assert!(results.is_empty());
+}
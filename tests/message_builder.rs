@@ -0,0 +1,110 @@
+mod common;
+
+use common::addresses;
+use postal_rs::{MessageBuilder, PostalError, MAX_RECIPIENTS};
+
+fn builder() -> MessageBuilder {
+    MessageBuilder::new()
+        .to(&["example@gmail.com".to_owned()])
+        .from("test@yourserver.io")
+        .text("A test message")
+}
+
+fn build_error(builder: MessageBuilder) -> String {
+    match builder.build() {
+        Err(PostalError::InvalidMessage(reason)) => reason,
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn build() {
+    let message = builder().subject("Hi").tag("spring").build().unwrap();
+
+    assert_eq!(message.to, Some(vec!["example@gmail.com".to_owned()]));
+    assert_eq!(message.from.as_deref(), Some("test@yourserver.io"));
+    assert_eq!(message.subject.as_deref(), Some("Hi"));
+    assert_eq!(message.tag.as_deref(), Some("spring"));
+    assert_eq!(message.plain_body.as_deref(), Some("A test message"));
+}
+
+#[test]
+fn build_with_html_and_cc_only() {
+    let message = MessageBuilder::new()
+        .cc(&["example@gmail.com".to_owned()])
+        .from("test@yourserver.io")
+        .html("<p>A test message</p>")
+        .build()
+        .unwrap();
+
+    assert_eq!(message.to, None);
+    assert_eq!(message.plain_body, None);
+}
+
+#[test]
+fn build_with_max_recipients() {
+    builder().to(&addresses(MAX_RECIPIENTS)).build().unwrap();
+}
+
+#[test]
+fn no_recipients() {
+    let builder = MessageBuilder::new()
+        .from("test@yourserver.io")
+        .text("A test message");
+
+    assert_eq!(build_error(builder), "no recipients were set");
+}
+
+#[test]
+fn too_many_recipients() {
+    let many = addresses(MAX_RECIPIENTS + 1);
+
+    assert_eq!(
+        build_error(builder().to(&many)),
+        "too many recipients in `to` (51 > 50)"
+    );
+    assert_eq!(
+        build_error(builder().cc(&many)),
+        "too many recipients in `cc` (51 > 50)"
+    );
+    assert_eq!(
+        build_error(builder().bcc(&many)),
+        "too many recipients in `bcc` (51 > 50)"
+    );
+}
+
+#[test]
+fn no_from() {
+    let builder = MessageBuilder::new()
+        .to(&["example@gmail.com".to_owned()])
+        .text("A test message");
+
+    assert_eq!(build_error(builder), "a `from` address is not set");
+}
+
+#[test]
+fn no_body() {
+    let builder = MessageBuilder::new()
+        .to(&["example@gmail.com".to_owned()])
+        .from("test@yourserver.io");
+
+    assert_eq!(build_error(builder), "no body was set");
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn send_builder() {
+    let mock = postal_rs::testing::MockPostal::start().await;
+    let client = mock.client();
+
+    let results = client.send_builder(builder()).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].to, "example@gmail.com");
+
+    let err = client
+        .send_builder(MessageBuilder::new().from("test@yourserver.io"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PostalError::InvalidMessage(_)), "{:?}", err);
+    assert_eq!(mock.requests().len(), 1);
+}
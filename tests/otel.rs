@@ -0,0 +1,63 @@
+#![cfg(all(feature = "otel", feature = "testing"))]
+
+mod common;
+
+use common::message;
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use opentelemetry::Context;
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use postal_rs::{Client, TRACEPARENT_HEADER, TRACESTATE_HEADER};
+
+fn parent() -> Context {
+    let state = TraceState::from_key_value(vec![("vendor", "value")]).unwrap();
+    let span_context = SpanContext::new(
+        TraceId::from_bytes(0x4bf92f3577b34da6a3ce929d0e0e4736u128.to_be_bytes()),
+        SpanId::from_bytes(0x00f067aa0ba902b7u64.to_be_bytes()),
+        TraceFlags::SAMPLED,
+        true,
+        state,
+    );
+
+    Context::current().with_remote_span_context(span_context)
+}
+
+#[tokio::test]
+async fn parent_context_is_propagated() {
+    let mock = MockPostal::start().await;
+    let client = mock.client();
+
+    let _guard = parent().attach();
+    client.send(message()).await.unwrap();
+
+    let request = &mock.requests()[0];
+    assert_eq!(
+        request.header(TRACEPARENT_HEADER),
+        Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+    );
+    assert_eq!(request.header(TRACESTATE_HEADER), Some("vendor=value"));
+}
+
+#[tokio::test]
+async fn no_active_context() {
+    let mock = MockPostal::start().await;
+
+    mock.client().send(message()).await.unwrap();
+
+    let request = &mock.requests()[0];
+    assert_eq!(request.header(TRACEPARENT_HEADER), None);
+    assert_eq!(request.header(TRACESTATE_HEADER), None);
+}
+
+#[tokio::test]
+async fn propagation_disabled() {
+    let mock = MockPostal::start().await;
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .propagate_context(false)
+        .build()
+        .unwrap();
+
+    let _guard = parent().attach();
+    client.send(message()).await.unwrap();
+
+    assert_eq!(mock.requests()[0].header(TRACEPARENT_HEADER), None);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "testing")]
+
+use postal_rs::testing::MockPostal;
+use postal_rs::PostalError;
+use serde_json::json;
+
+#[tokio::test]
+async fn arbitrary_endpoint() {
+    let mock = MockPostal::start().await;
+    mock.respond_success("/api/v1/suppressions/list", json!({ "total": 0 }));
+
+    let data = mock
+        .client()
+        .request_raw("api/v1/suppressions/list", json!({ "page": 1 }))
+        .await
+        .unwrap();
+
+    assert_eq!(data, json!({ "total": 0 }));
+    let requests = mock.requests();
+    assert_eq!(requests[0].path, "/api/v1/suppressions/list");
+    assert_eq!(requests[0].json().unwrap(), json!({ "page": 1 }));
+}
+
+#[tokio::test]
+async fn unknown_endpoint() {
+    let mock = MockPostal::start().await;
+
+    let err = mock
+        .client()
+        .request_raw("api/v1/no/such/method", json!({}))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, PostalError::UnexpectedStatus(status) if status.as_u16() == 404),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn error_statuses() {
+    for &status in &[401, 403, 404, 429, 502] {
+        let mock = MockPostal::start().await;
+        mock.respond("/api/v1/custom", status, "");
+
+        let result = mock.client().request_raw("api/v1/custom", json!({})).await;
+
+        assert!(result.is_err(), "{}", status);
+    }
+}
+
+#[tokio::test]
+async fn error_response() {
+    let mock = MockPostal::start().await;
+    mock.respond_error("/api/v1/custom", "AccessDenied", "Must be authenticated");
+
+    let err = mock
+        .client()
+        .request_raw("api/v1/custom", json!({}))
+        .await
+        .unwrap_err();
+
+    assert!(err.is_auth_error(), "{:?}", err);
+}
+
+#[tokio::test]
+async fn prefixed_base_url() {
+    let mock = MockPostal::start().await;
+    mock.respond_success("/postal/api/v1/custom", json!(1));
+    let client = mock
+        .client()
+        .with_base_url(format!("{}postal", mock.url()))
+        .unwrap();
+
+    let data = client
+        .request_raw("api/v1/custom", json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(data, json!(1));
+    assert_eq!(mock.requests()[0].path, "/postal/api/v1/custom");
+}
@@ -0,0 +1,213 @@
+use postal_rs::{
+    Attachment, Delivery, Message, MessageDetails, MessageReport, MessageStatus, SendOutcome,
+    SendResult,
+};
+
+fn result(to: &str, id: u64) -> SendResult {
+    SendResult {
+        to: to.to_owned(),
+        id,
+        token: format!("token{}", id),
+    }
+}
+
+#[test]
+fn send_result_round_trip() {
+    let results = vec![result("a@example.com", 1), result("b@example.com", 2)];
+
+    let json = serde_json::to_string(&results).unwrap();
+    let loaded: Vec<SendResult> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, results);
+    assert_eq!(
+        serde_json::to_value(&results[0]).unwrap(),
+        serde_json::json!({ "to": "a@example.com", "id": 1, "token": "token1" })
+    );
+}
+
+#[test]
+fn send_result_without_token() {
+    let loaded: SendResult = serde_json::from_str(r#"{"to": "a@example.com", "id": 7}"#).unwrap();
+
+    assert_eq!(loaded.to, "a@example.com");
+    assert_eq!(loaded.id, 7);
+    assert_eq!(loaded.token, "");
+}
+
+#[test]
+fn send_result_display() {
+    assert_eq!(
+        result("a@example.com", 1).to_string(),
+        "a@example.com -> #1"
+    );
+    assert_eq!(
+        result("John <john@example.com>", 42).to_string(),
+        "John <john@example.com> -> #42"
+    );
+}
+
+#[test]
+fn message_display() {
+    let mut message = Message::default()
+        .to(&["a@example.com".to_owned(), "b@example.com".to_owned()])
+        .from("test@yourserver.io")
+        .subject("Hello \"World\"")
+        .text("a secret text")
+        .html("<p>a secret html</p>")
+        .attachment(Attachment::new("secret.pdf", "application/pdf", "%PDF"));
+    message.bcc = Some(vec!["c@example.com".to_owned()]);
+
+    let summary = message.to_string();
+
+    assert_eq!(
+        summary,
+        r#""Hello \"World\"" to 2 recipient(s), cc 0, bcc 1"#
+    );
+    assert!(!summary.contains("secret"));
+}
+
+#[test]
+fn message_display_without_subject() {
+    assert_eq!(
+        Message::default().to_string(),
+        r#""" to 0 recipient(s), cc 0, bcc 0"#
+    );
+}
+
+#[test]
+fn send_outcome_round_trip() {
+    let outcome = SendOutcome {
+        accepted: vec![result("a@example.com", 1)],
+        missing: vec!["b@example.com".to_owned()],
+    };
+
+    let json = serde_json::to_string(&outcome).unwrap();
+    let loaded: SendOutcome = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, outcome);
+}
+
+fn round_trip<T>(value: &T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    let loaded: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(&loaded, value, "{}", json);
+}
+
+fn details() -> MessageDetails {
+    serde_json::from_value(serde_json::json!({
+        "id": 12,
+        "token": "abcdef",
+        "status": {
+            "status": "SoftFail",
+            "last_delivery_attempt": 1600000000.5,
+            "held": 0,
+            "hold_expiry": null,
+        },
+        "details": {
+            "rcpt_to": "to@example.com",
+            "mail_from": "from@example.com",
+            "subject": "Hello",
+            "message_id": "<1@example.com>",
+            "timestamp": 1600000000.25,
+            "direction": "outgoing",
+            "size": "1024",
+            "bounce": 0,
+            "bounce_for_id": 0,
+            "tag": "spring",
+            "received_with_ssl": 1,
+        },
+        "inspection": {
+            "inspected": 1,
+            "spam": false,
+            "spam_score": 2.5,
+            "threat": 0,
+            "threat_details": null,
+            "spam_checks": [
+                { "code": "MISSING_DATE", "score": 2.5, "description": "Missing Date: header" }
+            ],
+        },
+        "plain_body": "Hi",
+        "headers": { "subject": ["Hello"] },
+    }))
+    .unwrap()
+}
+
+fn delivery() -> Delivery {
+    serde_json::from_value(serde_json::json!({
+        "id": 3,
+        "status": "SoftFail",
+        "details": "Temporary failure",
+        "output": "452 4.2.2 Mailbox full",
+        "sent_with_ssl": 1,
+        "log_id": "XYZ",
+        "time": 0.12,
+        "timestamp": 1600000000.0,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn message_details_round_trip() {
+    let details = details();
+
+    round_trip(&details);
+    round_trip(details.status.as_ref().unwrap());
+    round_trip(details.details.as_ref().unwrap());
+    round_trip(details.inspection.as_ref().unwrap());
+}
+
+#[test]
+fn message_status_round_trip() {
+    for status in &[
+        MessageStatus::Pending,
+        MessageStatus::Processed,
+        MessageStatus::Sent,
+        MessageStatus::SoftFail,
+        MessageStatus::HardFail,
+        MessageStatus::Held,
+        MessageStatus::Bounced,
+        MessageStatus::Unknown("MXError".to_owned()),
+    ] {
+        round_trip(status);
+    }
+}
+
+#[test]
+fn delivery_round_trip() {
+    let delivery = delivery();
+    assert_eq!(delivery.smtp_code, Some(452));
+
+    round_trip(&delivery);
+}
+
+#[test]
+fn message_report_round_trip() {
+    let report = MessageReport {
+        details: details(),
+        deliveries: vec![delivery()],
+    };
+
+    round_trip(&report);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn timestamps_as_dates() {
+    use chrono::{TimeZone, Utc};
+
+    let at = |millis: i64| {
+        Utc.timestamp_millis_opt(1_600_000_000_000 + millis)
+            .unwrap()
+    };
+    let details = details();
+    let status = details.status.unwrap();
+
+    assert_eq!(details.details.unwrap().timestamp_utc(), Some(at(250)));
+    assert_eq!(status.last_delivery_attempt_utc(), Some(at(500)));
+    assert_eq!(status.hold_expiry_utc(), None);
+    assert_eq!(delivery().timestamp_utc(), Some(at(0)));
+    assert_eq!(at(0).to_rfc3339(), "2020-09-13T12:26:40+00:00");
+}
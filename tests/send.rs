@@ -0,0 +1,323 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::{addresses, message};
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use postal_rs::{Client, DetailsInterest, PostalError, PostalErrorCode};
+use serde_json::json;
+
+const SEND: &str = "/api/v1/send/message";
+const DETAILS: &str = "/api/v1/messages/message";
+
+#[tokio::test]
+async fn send_and_inspect() {
+    let mock = MockPostal::start().await;
+    let message = message().to(&["a@gmail.com".to_owned(), "b@gmail.com".to_owned()]);
+
+    let reports = mock
+        .client()
+        .send_and_inspect(message, |id| DetailsInterest::new(id).with_status())
+        .await
+        .unwrap();
+
+    assert_eq!(reports.len(), 2);
+    for (result, details) in &reports {
+        assert_eq!(result.id, details.id);
+    }
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].path, SEND);
+    for (request, (result, _)) in requests[1..].iter().zip(&reports) {
+        assert_eq!(request.path, DETAILS);
+        let json = request.json().unwrap();
+        assert_eq!(json["id"], result.id);
+        assert_eq!(json["_expansions"], serde_json::json!(["status"]));
+    }
+}
+
+#[tokio::test]
+async fn send_and_inspect_fails_on_details() {
+    let mock = MockPostal::start().await;
+    mock.respond_error(DETAILS, "MessageNotFound", "No message found");
+
+    let err = mock
+        .client()
+        .send_and_inspect(message(), DetailsInterest::new)
+        .await
+        .unwrap_err();
+
+    assert!(err.is_not_found(), "{:?}", err);
+}
+
+#[tokio::test]
+async fn send_and_inspect_fails_on_send() {
+    let mock = MockPostal::start().await;
+    mock.respond(SEND, 500, "");
+
+    let err = mock
+        .client()
+        .send_and_inspect(message(), DetailsInterest::new)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, PostalError::InternalServerError), "{:?}", err);
+    assert_eq!(mock.requests().len(), 1);
+}
+
+fn accepted(recipients: &[&str]) -> serde_json::Value {
+    let messages: serde_json::Map<String, serde_json::Value> = recipients
+        .iter()
+        .enumerate()
+        .map(|(i, to)| (to.to_string(), json!({ "id": i + 1, "token": "abc" })))
+        .collect();
+    json!({ "message_id": "x@postal", "messages": messages })
+}
+
+#[tokio::test]
+async fn outcome_with_missing_recipient() {
+    let mock = MockPostal::start().await;
+    mock.respond_success(SEND, accepted(&["a@gmail.com", "c@gmail.com"]));
+    let mut message = message().to(&["a@gmail.com".to_owned(), "B <b@gmail.com>".to_owned()]);
+    message.cc = Some(vec!["C@gmail.com".to_owned()]);
+
+    let outcome = mock.client().send_with_outcome(message).await.unwrap();
+
+    let accepted: Vec<_> = outcome.accepted.iter().map(|r| r.to.as_str()).collect();
+    assert_eq!(accepted.len(), 2);
+    assert!(accepted.contains(&"a@gmail.com") && accepted.contains(&"c@gmail.com"));
+    assert_eq!(outcome.missing, ["B <b@gmail.com>"]);
+    assert!(!outcome.is_complete());
+}
+
+#[tokio::test]
+async fn complete_outcome() {
+    let mock = MockPostal::start().await;
+    let message = message().to(&["a@gmail.com".to_owned(), "b@gmail.com".to_owned()]);
+
+    let outcome = mock.client().send_with_outcome(message).await.unwrap();
+
+    assert_eq!(outcome.accepted.len(), 2);
+    assert!(outcome.missing.is_empty());
+    assert!(outcome.is_complete());
+}
+
+fn sent_lists(mock: &MockPostal, field: &str) -> Vec<usize> {
+    mock.requests()
+        .iter()
+        .map(|request| {
+            request.json().unwrap()[field]
+                .as_array()
+                .map_or(0, Vec::len)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn send_chunked() {
+    let mock = MockPostal::start().await;
+    let mut message = message().to(&addresses(120));
+    message.cc = Some(vec!["cc@gmail.com".to_owned()]);
+    message.bcc = Some(vec!["bcc@gmail.com".to_owned()]);
+
+    let results = mock.client().send_chunked(message, 50).await.unwrap();
+
+    assert_eq!(results.len(), 122);
+    assert_eq!(sent_lists(&mock, "to"), [50, 50, 20]);
+    // cc and bcc get a single copy
+    assert_eq!(sent_lists(&mock, "cc"), [1, 0, 0]);
+    assert_eq!(sent_lists(&mock, "bcc"), [1, 0, 0]);
+
+    let sent: Vec<String> = mock
+        .requests()
+        .iter()
+        .flat_map(|request| {
+            let to = request.json().unwrap()["to"].as_array().unwrap().clone();
+            to.into_iter().map(|to| to.as_str().unwrap().to_owned())
+        })
+        .collect();
+    assert_eq!(sent, addresses(120));
+}
+
+#[tokio::test]
+async fn send_chunked_caps_chunk_size() {
+    let mock = MockPostal::start().await;
+
+    mock.client()
+        .send_chunked(message().to(&addresses(120)), 100)
+        .await
+        .unwrap();
+
+    assert_eq!(sent_lists(&mock, "to"), [50, 50, 20]);
+}
+
+#[tokio::test]
+async fn send_chunked_with_custom_size() {
+    let mock = MockPostal::start().await;
+
+    mock.client()
+        .send_chunked(message().to(&addresses(7)), 3)
+        .await
+        .unwrap();
+
+    assert_eq!(sent_lists(&mock, "to"), [3, 3, 1]);
+}
+
+#[tokio::test]
+async fn send_chunked_partially() {
+    let mock = MockPostal::start().await;
+    let first: Vec<String> = addresses(50);
+    let first: Vec<&str> = first.iter().map(String::as_str).collect();
+    mock.respond_success(SEND, accepted(&first));
+    mock.respond(SEND, 503, "");
+
+    let err = mock
+        .client()
+        .send_chunked(message().to(&addresses(120)), 50)
+        .await
+        .unwrap_err();
+
+    match err {
+        PostalError::PartiallySent { sent, source } => {
+            assert_eq!(sent.len(), 50);
+            assert!(matches!(*source, PostalError::ServiceUnavailableError));
+        }
+        err => panic!("{:?}", err),
+    }
+    // the remaining chunk isn't sent
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn bounce_with_sender() {
+    let mock = MockPostal::start().await;
+    let message = message()
+        .from("author@yourserver.io")
+        .sender("bounces@yourserver.io")
+        .bounce(true);
+
+    mock.client().send(message.clone()).await.unwrap();
+
+    let json = mock.requests()[0].json().unwrap();
+    assert_eq!(json["from"], "author@yourserver.io");
+    assert_eq!(json["sender"], "bounces@yourserver.io");
+    assert_eq!(json["bounce"], true);
+
+    let raw = message.into_raw_message().unwrap();
+    assert_eq!(raw.mail_from, "bounces@yourserver.io");
+    assert_eq!(raw.bounce, Some(true));
+}
+
+#[tokio::test]
+async fn send_batch_keeps_order() {
+    let mock = MockPostal::start().await;
+    let messages = addresses(3)
+        .into_iter()
+        .map(|to| message().to(&[to]))
+        .collect();
+
+    let results = mock.client().send_batch(messages).await;
+
+    let sent_to: Vec<_> = results
+        .into_iter()
+        .map(|result| {
+            let result = result.unwrap();
+            assert_eq!(result.len(), 1);
+            result[0].to.clone()
+        })
+        .collect();
+    assert_eq!(sent_to, addresses(3));
+    assert_eq!(mock.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn send_batch_with_a_failure() {
+    let mock = MockPostal::start().await;
+    mock.respond_success(SEND, accepted(&["user0@gmail.com"]));
+    mock.respond_error(SEND, "NoRecipients", "There are no recipients defined");
+    mock.respond_success(SEND, accepted(&["user2@gmail.com"]));
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .batch_concurrency(1)
+        .build()
+        .unwrap();
+    let messages = addresses(3)
+        .into_iter()
+        .map(|to| message().to(&[to]))
+        .collect();
+
+    let results = client.send_batch(messages).await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap()[0].to, "user0@gmail.com");
+    assert!(matches!(
+        &results[1],
+        Err(PostalError::Error {
+            code: PostalErrorCode::NoRecipients,
+            ..
+        })
+    ));
+    assert_eq!(results[2].as_ref().unwrap()[0].to, "user2@gmail.com");
+}
+
+#[tokio::test]
+async fn checks_are_left_to_postal() {
+    let mock = MockPostal::start().await;
+    mock.respond_error(SEND, "NoRecipients", "There are no recipients defined");
+
+    let err = mock.client().send(message().to(&[])).await.unwrap_err();
+
+    assert!(
+        matches!(
+            &err,
+            PostalError::Error {
+                code: PostalErrorCode::NoRecipients,
+                ..
+            }
+        ),
+        "{:?}",
+        err
+    );
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn send_one() {
+    let mock = MockPostal::start().await;
+
+    let result = mock.client().send_one(message()).await.unwrap();
+
+    assert_eq!(result.to, "example@gmail.com");
+}
+
+#[tokio::test]
+async fn send_one_without_results() {
+    let mock = MockPostal::start().await;
+    mock.respond_success(SEND, accepted(&[]));
+
+    let err = mock.client().send_one(message()).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        PostalError::UnexpectedRecipientCount {
+            expected: 1,
+            got: 0
+        }
+    ));
+}
+
+#[tokio::test]
+async fn send_one_with_several_results() {
+    let mock = MockPostal::start().await;
+    let message = message().to(&["a@gmail.com".to_owned(), "b@gmail.com".to_owned()]);
+
+    let err = mock.client().send_one(message).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        PostalError::UnexpectedRecipientCount {
+            expected: 1,
+            got: 2
+        }
+    ));
+}
@@ -0,0 +1,68 @@
+use postal_rs::{Client, PostalError, API_KEY_HEADER, TOKEN_ENV};
+use std::{env, fs, path::PathBuf};
+
+const URL: &str = "https://postal.example.com";
+
+fn token_of(client: &Client) -> String {
+    let request = client.request_builder("api/v1/send/message").unwrap();
+    let request = request.build().unwrap();
+    request.headers()[API_KEY_HEADER]
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+fn token_file(name: &str, content: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("postal-rs-{}-{}", std::process::id(), name));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+// The only test which touches the environment,
+// so the cases run one after another.
+#[test]
+fn token_from_env() {
+    env::set_var(TOKEN_ENV, " env-token\n");
+    let client = Client::from_env(URL).unwrap();
+    assert_eq!(token_of(&client), "env-token");
+
+    env::set_var(TOKEN_ENV, " \n");
+    let err = Client::from_env(URL).unwrap_err();
+    assert!(matches!(err, PostalError::MissingToken), "{:?}", err);
+
+    env::remove_var(TOKEN_ENV);
+    let err = Client::from_env(URL).unwrap_err();
+    assert!(matches!(err, PostalError::MissingToken), "{:?}", err);
+}
+
+#[test]
+fn token_from_file() {
+    let path = token_file("token", "file-token\n");
+
+    let client = Client::from_token_file(URL, &path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(token_of(&client), "file-token");
+}
+
+#[test]
+fn empty_token_file() {
+    let path = token_file("empty", "\n\t \n");
+
+    let err = Client::from_token_file(URL, &path).unwrap_err();
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(err, PostalError::MissingToken), "{:?}", err);
+}
+
+#[test]
+fn missing_token_file() {
+    let path = env::temp_dir().join("postal-rs-missing-token");
+
+    let err = Client::from_token_file(URL, &path).unwrap_err();
+
+    match err {
+        PostalError::Io(err) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+        err => panic!("unexpected error {:?}", err),
+    }
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::message;
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use postal_rs::{Client, PostalError, TokenContext, API_KEY_HEADER};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[tokio::test]
+async fn derived_clients_share_a_pool() {
+    let mock = MockPostal::start().await;
+    let client = mock.client();
+    let first = client.with_token("first").unwrap();
+    let second = client.with_token("second").unwrap();
+
+    first.send(message()).await.unwrap();
+    second.send(message()).await.unwrap();
+    client.send(message()).await.unwrap();
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].header("X-Server-API-Key"), Some("first"));
+    assert_eq!(requests[1].header("X-Server-API-Key"), Some("second"));
+    assert_eq!(requests[2].header("X-Server-API-Key"), Some(MOCK_TOKEN));
+    // the connection of the first request is reused
+    assert_eq!(requests[0].peer, requests[1].peer);
+    assert_eq!(requests[0].peer, requests[2].peer);
+}
+
+#[tokio::test]
+async fn blank_derived_token() {
+    let client = MockPostal::start().await.client();
+
+    for token in &["", " ", "\n\t"] {
+        let err = client.with_token(*token).unwrap_err();
+        assert!(matches!(err, PostalError::MissingToken), "{:?}", err);
+    }
+}
+
+#[tokio::test]
+async fn default_key_header() {
+    let mock = MockPostal::start().await;
+
+    mock.client().send(message()).await.unwrap();
+
+    let request = &mock.requests()[0];
+    assert_eq!(request.header(API_KEY_HEADER), Some(MOCK_TOKEN));
+    assert_eq!(request.header("Authorization"), None);
+}
+
+#[tokio::test]
+async fn custom_key_header() {
+    let mock = MockPostal::start().await;
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .api_key_header("X-Gateway-Key")
+        .build()
+        .unwrap();
+
+    client.send(message()).await.unwrap();
+    client.get_message_details(1).await.unwrap();
+
+    for request in mock.requests() {
+        assert_eq!(request.header("X-Gateway-Key"), Some(MOCK_TOKEN));
+        assert_eq!(request.header(API_KEY_HEADER), None);
+    }
+}
+
+#[tokio::test]
+async fn bearer_auth() {
+    let mock = MockPostal::start().await;
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .bearer_auth(true)
+        .build()
+        .unwrap();
+
+    client.send(message()).await.unwrap();
+
+    let request = &mock.requests()[0];
+    assert_eq!(request.header(API_KEY_HEADER), Some(MOCK_TOKEN));
+    let bearer = format!("Bearer {}", MOCK_TOKEN);
+    assert_eq!(request.header("Authorization"), Some(bearer.as_str()));
+}
+
+#[tokio::test]
+async fn token_per_request() {
+    let mock = MockPostal::start().await;
+    let calls = AtomicUsize::new(0);
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .token_provider(move |_: &TokenContext<'_>| {
+            format!("token-{}", calls.fetch_add(1, Ordering::SeqCst))
+        })
+        .build()
+        .unwrap();
+
+    client.send(message()).await.unwrap();
+    client.send(message()).await.unwrap();
+    client.get_message_details(1).await.unwrap();
+
+    let tokens: Vec<_> = mock
+        .requests()
+        .iter()
+        .map(|request| request.header(API_KEY_HEADER).unwrap().to_owned())
+        .collect();
+    assert_eq!(tokens, ["token-0", "token-1", "token-2"]);
+}
+
+#[tokio::test]
+async fn token_by_context() {
+    let mock = MockPostal::start().await;
+    let client = Client::builder(mock.url(), MOCK_TOKEN)
+        .token_provider(|context: &TokenContext<'_>| match context.from {
+            Some(from) if from.ends_with("@tenant.io") => "tenant".to_owned(),
+            Some(_) => "default".to_owned(),
+            None => format!("no-sender {}", context.path),
+        })
+        .build()
+        .unwrap();
+
+    client.send(message().from("a@tenant.io")).await.unwrap();
+    client.send(message()).await.unwrap();
+    client.get_message_details(1).await.unwrap();
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].header(API_KEY_HEADER), Some("tenant"));
+    assert_eq!(requests[1].header(API_KEY_HEADER), Some("default"));
+    assert_eq!(
+        requests[2].header(API_KEY_HEADER),
+        Some("no-sender api/v1/messages/message")
+    );
+}
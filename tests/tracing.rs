@@ -0,0 +1,108 @@
+#![cfg(all(feature = "tracing", feature = "testing"))]
+
+mod common;
+
+use common::message;
+use postal_rs::testing::{MockPostal, MOCK_TOKEN};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_core::span::Current;
+
+type Fields = HashMap<&'static str, String>;
+
+/// Records fields of all spans and events
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<(&'static Metadata<'static>, Fields)>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+    entered: Arc<Mutex<Vec<Id>>>,
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl Visit for Visitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::new();
+        span.record(&mut Visitor(&mut fields));
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((span.metadata(), fields));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut Visitor(fields));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.clone());
+    }
+
+    fn exit(&self, _: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+
+    // `Span::current` is needed to record fields inside of a span
+    fn current_span(&self) -> Current {
+        match self.entered.lock().unwrap().last() {
+            Some(span) => {
+                let (metadata, _) = self.spans.lock().unwrap()[span.into_u64() as usize - 1];
+                Current::new(span.clone(), metadata)
+            }
+            None => Current::none(),
+        }
+    }
+}
+
+#[tokio::test]
+async fn send_is_traced() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+    let mock = MockPostal::start().await;
+
+    mock.client().send(message()).await.unwrap();
+
+    let spans = recorder.spans.lock().unwrap();
+    let sends: Vec<_> = spans
+        .iter()
+        .filter(|(_, fields)| fields.contains_key("endpoint"))
+        .collect();
+    assert_eq!(sends.len(), 1, "{:?}", spans);
+    let (_, fields) = sends[0];
+    assert_eq!(fields["endpoint"], "\"send/message\"");
+    assert_eq!(fields["recipients"], "1");
+
+    let events = recorder.events.lock().unwrap();
+    let values = spans
+        .iter()
+        .map(|(_, fields)| fields)
+        .chain(events.iter())
+        .flat_map(|fields| fields.values());
+    for value in values {
+        assert!(!value.contains(MOCK_TOKEN), "{}", value);
+    }
+}